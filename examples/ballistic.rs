@@ -1,4 +1,6 @@
-use phys_buzz::Particle;
+use phys_buzz::math::Vector3;
+use phys_buzz::particle_forces::{Atmosphere, ParticleAerodynamics};
+use phys_buzz::{Particle, ParticleBuilder, ParticleForceGenerator};
 
 use bevy::{prelude::*, render::camera::PerspectiveProjection};
 use utilities::PhysBuzzDemoPlugin;
@@ -6,6 +8,9 @@ use utilities::PhysBuzzDemoPlugin;
 // Tracks whether a particle has been alive for a given length of time
 struct LifeTimer(Timer);
 
+// Air resistance on a shot, which thins out with altitude
+struct AirResistance(ParticleAerodynamics);
+
 // Global assets for all particles and shadows
 struct ParticleAssets {
     mesh: Handle<Mesh>,
@@ -191,18 +196,26 @@ fn setup(
 fn simulate(
     mut commands: Commands,
     time: Res<Time>,
-    mut query: Query<(Entity, &mut Particle, &mut LifeTimer)>,
+    mut query: Query<(
+        Entity,
+        &mut Particle,
+        &mut LifeTimer,
+        Option<&mut AirResistance>,
+    )>,
 ) {
     let duration = time.delta_seconds();
     if duration <= 0.0 {
         return;
     }
 
-    for (entity, mut particle, mut life_timer) in query.iter_mut() {
+    for (entity, mut particle, mut life_timer, air_resistance) in query.iter_mut() {
         let position = particle.get_position();
         if life_timer.0.tick(time.delta()).finished() || position.y < 0.0 || position.z > 200.0 {
             commands.entity(entity).despawn();
         } else {
+            if let Some(mut air_resistance) = air_resistance {
+                air_resistance.0.update_force(&mut particle, duration);
+            }
             particle.integrate(duration);
         }
     }
@@ -216,30 +229,31 @@ fn click_spawn_projectile(
     mouse_input: Res<Input<MouseButton>>,
 ) {
     if mouse_input.just_pressed(MouseButton::Left) {
-        let mut particle = Particle::default();
-        particle.set_position(0.0, 1.5, 0.0);
+        let particle = match *shot_type {
+            ShotType::PISTOL => ParticleBuilder::pistol(),
+            ShotType::ARTILLERY => ParticleBuilder::artillery(),
+            ShotType::FIREBALL => ParticleBuilder::fireball(),
+            ShotType::LASER => ParticleBuilder::laser(),
+        }
+        .position(Vector3::new(0.0, 1.5, 0.0))
+        .build();
 
-        let (mass, vel, grav, damping) = match *shot_type {
-            ShotType::PISTOL => (2.0, (0.0, 35.0), -1.0, 0.99),
-            ShotType::ARTILLERY => (200.0, (30.0, 40.0), -20.0, 0.99),
-            ShotType::FIREBALL => (1.0, (0.0, 10.0), 0.6, 0.9),
-            ShotType::LASER => (0.1, (0.0, 100.0), 0.0, 0.99),
-        };
-        particle.set_mass(mass);
-        particle.set_velocity(0.0, vel.0, vel.1);
-        particle.set_acceleration(0.0, grav, 0.0);
-        particle.set_damping(damping);
+        let mut shot = commands.spawn_bundle(PbrBundle {
+            mesh: assets.mesh.clone(),
+            material: assets.material.clone(),
+            transform: Transform::from_xyz(0.0, 1.5, 0.0),
+            ..Default::default()
+        });
+        shot.insert(particle)
+            .insert(LifeTimer(Timer::from_seconds(5.0, false)));
 
-        let particle_id = commands
-            .spawn_bundle(PbrBundle {
-                mesh: assets.mesh.clone(),
-                material: assets.material.clone(),
-                transform: Transform::from_xyz(0.0, 1.5, 0.0),
-                ..Default::default()
-            })
-            .insert(particle)
-            .insert(LifeTimer(Timer::from_seconds(5.0, false)))
-            .id();
+        // Artillery shells feel air resistance, which thins out as they climb
+        if let ShotType::ARTILLERY = *shot_type {
+            let mut aerodynamics = ParticleAerodynamics::ball(0.3, 0.0775);
+            aerodynamics.set_atmosphere(Some(Atmosphere::standard()));
+            shot.insert(AirResistance(aerodynamics));
+        }
+        let particle_id = shot.id();
 
         // A shadow below the particle
         commands
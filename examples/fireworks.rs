@@ -0,0 +1,217 @@
+use phys_buzz::math::{Random, Vector3};
+use phys_buzz::{Firework, FireworkRule};
+
+use bevy::{prelude::*, render::camera::PerspectiveProjection};
+
+// The rules describing each firework type, indexed by type
+struct FireworkRules(Vec<FireworkRule>);
+
+// Randomness shared by every firework launch and burst
+struct FireworkRandom(Random);
+
+// Global assets for all fireworks, with a material for each type
+struct FireworkAssets {
+    mesh: Handle<Mesh>,
+    materials: Vec<Handle<StandardMaterial>>,
+}
+
+// The key pressed to launch each firework type
+const LAUNCH_KEYS: [KeyCode; 9] = [
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+];
+
+fn main() {
+    App::build()
+        .insert_resource(Msaa { samples: 4 })
+        .add_plugins(DefaultPlugins)
+        .insert_resource(ClearColor(Color::rgb(0.05, 0.05, 0.1)))
+        .insert_resource(FireworkRules(FireworkRule::classic()))
+        .insert_resource(FireworkRandom(Random::new(0)))
+        .add_startup_system(setup.system())
+        .add_system(simulate.system())
+        .add_system(click_launch.system())
+        .add_system(key_launch.system())
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut windows: ResMut<Windows>,
+) {
+    let window = windows.get_primary_mut().unwrap();
+    window.set_title("PhysBuzz - Fireworks Demo".to_string());
+
+    commands.spawn_bundle(UiCameraBundle::default());
+
+    // An overhead light source
+    commands.spawn_bundle(LightBundle {
+        transform: Transform::from_xyz(4.0, 20.0, 10.0),
+        ..Default::default()
+    });
+
+    // A camera looking at the launch area from the front
+    commands.spawn_bundle(PerspectiveCameraBundle {
+        transform: Transform::from_xyz(0.0, 8.0, 30.0)
+            .looking_at(Vec3::new(0.0, 8.0, 0.0), Vec3::Y),
+        perspective_projection: PerspectiveProjection {
+            fov: std::f32::consts::PI / 3.0,
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+
+    // The ground fireworks are launched from
+    commands.spawn_bundle(PbrBundle {
+        mesh: meshes.add(Mesh::from(shape::Plane { size: 40.0 })),
+        material: materials.add(Color::rgb(0.2, 0.25, 0.2).into()),
+        ..Default::default()
+    });
+
+    // Initialize the assets used for all fireworks, coloured by type
+    let colors = [
+        Color::rgb(1.0, 0.0, 0.0),
+        Color::rgb(1.0, 0.5, 0.0),
+        Color::rgb(1.0, 1.0, 0.0),
+        Color::rgb(0.0, 1.0, 0.0),
+        Color::rgb(0.0, 1.0, 1.0),
+        Color::rgb(0.4, 0.4, 1.0),
+        Color::rgb(1.0, 0.0, 1.0),
+        Color::rgb(1.0, 1.0, 1.0),
+        Color::rgb(1.0, 0.5, 0.5),
+    ];
+    commands.insert_resource(FireworkAssets {
+        mesh: meshes.add(Mesh::from(shape::Icosphere {
+            radius: 0.15,
+            subdivisions: 2,
+        })),
+        materials: colors
+            .iter()
+            .map(|&color| {
+                materials.add(StandardMaterial {
+                    base_color: color,
+                    unlit: true,
+                    ..Default::default()
+                })
+            })
+            .collect(),
+    });
+
+    // Instructions in the top left
+    let font = asset_server.load("fonts/FiraSans-Bold.ttf");
+    commands.spawn_bundle(TextBundle {
+        text: Text::with_section(
+            "Click: Launch\n1-9: Launch Firework Type",
+            TextStyle {
+                font,
+                font_size: 20.0,
+                color: Color::rgb(0.5, 0.5, 1.0),
+            },
+            Default::default(),
+        ),
+        style: Style {
+            position_type: PositionType::Absolute,
+            position: Rect {
+                top: Val::Px(20.0),
+                left: Val::Px(20.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+}
+
+// Spawn an entity to render and simulate a firework
+fn spawn_firework(commands: &mut Commands, assets: &FireworkAssets, firework: Firework) {
+    let position = firework.get_particle().get_position();
+    let material = assets.materials[firework.get_type() % assets.materials.len()].clone();
+    commands
+        .spawn_bundle(PbrBundle {
+            mesh: assets.mesh.clone(),
+            material,
+            transform: Transform::from_xyz(position.x, position.y, position.z),
+            ..Default::default()
+        })
+        .insert(firework);
+}
+
+// Launch fireworks of a type from a random point along the ground
+fn launch(
+    commands: &mut Commands,
+    assets: &FireworkAssets,
+    rules: &FireworkRules,
+    random: &mut FireworkRandom,
+    firework_type: usize,
+) {
+    let position = Vector3::new(random.0.range(-5.0, 5.0), 0.0, 0.0);
+    let firework =
+        rules.0[firework_type].create(firework_type, &mut random.0, position, Vector3::default());
+    spawn_firework(commands, assets, firework);
+}
+
+// Update every firework, replacing those which burst with their payloads
+fn simulate(
+    mut commands: Commands,
+    time: Res<Time>,
+    assets: Res<FireworkAssets>,
+    rules: Res<FireworkRules>,
+    mut random: ResMut<FireworkRandom>,
+    mut query: Query<(Entity, &mut Firework, &mut Transform)>,
+) {
+    let duration = time.delta_seconds();
+    if duration <= 0.0 {
+        return;
+    }
+
+    for (entity, mut firework, mut transform) in query.iter_mut() {
+        if firework.update(duration) {
+            commands.entity(entity).despawn();
+            let payloads = rules.0[firework.get_type()].burst(&firework, &rules.0, &mut random.0);
+            for payload in payloads {
+                spawn_firework(&mut commands, &assets, payload);
+            }
+        } else {
+            let position = firework.get_particle().get_position();
+            transform.translation = Vec3::new(position.x, position.y, position.z);
+        }
+    }
+}
+
+// Launch the main firework type when the user clicks
+fn click_launch(
+    mut commands: Commands,
+    assets: Res<FireworkAssets>,
+    rules: Res<FireworkRules>,
+    mut random: ResMut<FireworkRandom>,
+    mouse_input: Res<Input<MouseButton>>,
+) {
+    if mouse_input.just_pressed(MouseButton::Left) {
+        launch(&mut commands, &assets, &rules, &mut random, 0);
+    }
+}
+
+// Launch a firework of the type matching the number key pressed
+fn key_launch(
+    mut commands: Commands,
+    assets: Res<FireworkAssets>,
+    rules: Res<FireworkRules>,
+    mut random: ResMut<FireworkRandom>,
+    keyboard_input: Res<Input<KeyCode>>,
+) {
+    for (firework_type, &key) in LAUNCH_KEYS.iter().enumerate() {
+        if keyboard_input.just_pressed(key) && firework_type < rules.0.len() {
+            launch(&mut commands, &assets, &rules, &mut random, firework_type);
+        }
+    }
+}
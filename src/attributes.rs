@@ -0,0 +1,104 @@
+use crate::math::Real;
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+
+/// The radius of a particle treated as a sphere, e.g. a grain or a ball
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Radius(pub Real);
+
+/// The temperature of a particle, in kelvin
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Temperature(pub Real);
+
+/// The side a particle belongs to, e.g. to tell friendly fire apart
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Team(pub u32);
+
+/// Holds at most one value of each type, so that a particle can carry
+/// game data (e.g. `Radius`, `Team`, a `FrictionMaterial`, or any type
+/// of the user's own) for generators and callbacks to read
+#[derive(Default)]
+pub struct Attributes {
+    values: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+}
+
+impl Attributes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stores a value, returning the value of the same type it replaced
+    pub fn insert<T: Any + Send + Sync>(&mut self, value: T) -> Option<T> {
+        self.values
+            .insert(TypeId::of::<T>(), Box::new(value))
+            .and_then(|old| old.downcast().ok())
+            .map(|old| *old)
+    }
+
+    pub fn get<T: Any + Send + Sync>(&self) -> Option<&T> {
+        self.values
+            .get(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_ref())
+    }
+
+    pub fn get_mut<T: Any + Send + Sync>(&mut self) -> Option<&mut T> {
+        self.values
+            .get_mut(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_mut())
+    }
+
+    pub fn remove<T: Any + Send + Sync>(&mut self) -> Option<T> {
+        self.values
+            .remove(&TypeId::of::<T>())
+            .and_then(|value| value.downcast().ok())
+            .map(|value| *value)
+    }
+
+    pub fn contains<T: Any + Send + Sync>(&self) -> bool {
+        self.values.contains_key(&TypeId::of::<T>())
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Label(&'static str);
+
+    #[test]
+    fn holds_one_value_per_type() {
+        let mut attributes = Attributes::new();
+        assert!(attributes.is_empty());
+        assert_eq!(attributes.insert(Radius(0.5)), None);
+        assert_eq!(attributes.insert(Team(2)), None);
+        assert_eq!(attributes.insert(Radius(1.5)), Some(Radius(0.5)));
+        assert_eq!(attributes.len(), 2);
+
+        attributes.get_mut::<Team>().unwrap().0 = 3;
+        assert_eq!(attributes.get::<Team>(), Some(&Team(3)));
+        assert_eq!(attributes.get::<Temperature>(), None);
+    }
+
+    #[test]
+    fn stores_user_types() {
+        let mut attributes = Attributes::new();
+        attributes.insert(Label("spark"));
+        assert!(attributes.contains::<Label>());
+        assert_eq!(attributes.remove::<Label>(), Some(Label("spark")));
+        assert!(!attributes.contains::<Label>());
+    }
+}
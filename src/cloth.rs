@@ -0,0 +1,401 @@
+use crate::math::{Real, Vector3};
+use crate::particle::Particle;
+use crate::particle_forces::{ParticleForceGenerator, ParticleForceRegistry, ParticleSpring};
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// The role of a spring in a cloth
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClothSpringKind {
+    /// Joins neighbours along rows and columns, resisting stretching
+    Structural,
+
+    /// Joins diagonal neighbours, resisting shearing
+    Shear,
+
+    /// Joins particles two apart along rows and columns, resisting bending
+    Bend,
+}
+
+/// A spring in a cloth which has torn, between two particle indices
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClothTear {
+    pub a: usize,
+    pub b: usize,
+    pub kind: ClothSpringKind,
+}
+
+/// A spring between two particles of a cloth,
+/// made of a generator acting on each end
+struct ClothLink {
+    a: usize,
+    b: usize,
+    kind: ClothSpringKind,
+    on_a: Rc<RefCell<ParticleSpring>>,
+    on_b: Rc<RefCell<ParticleSpring>>,
+    torn: bool,
+}
+
+/// A rectangular grid of particles joined by springs
+pub struct Cloth {
+    rows: usize,
+    columns: usize,
+    particles: Vec<Rc<RefCell<Particle>>>,
+    links: Vec<ClothLink>,
+
+    /// The mass of each particle, restored when it is unpinned
+    particle_mass: Real,
+}
+
+impl Cloth {
+    pub fn get_rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn get_columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn index(&self, row: usize, column: usize) -> usize {
+        row * self.columns + column
+    }
+
+    pub fn particle(&self, row: usize, column: usize) -> &Rc<RefCell<Particle>> {
+        &self.particles[self.index(row, column)]
+    }
+
+    pub fn get_particles(&self) -> &[Rc<RefCell<Particle>>] {
+        &self.particles
+    }
+
+    /// Registers every spring of the cloth, adding further
+    /// generators (e.g. gravity) to every particle
+    pub fn register(
+        &self,
+        registry: &mut ParticleForceRegistry,
+        generators: &[Rc<RefCell<dyn ParticleForceGenerator>>],
+    ) {
+        for link in self.links.iter().filter(|link| !link.torn) {
+            registry.add(self.particles[link.a].clone(), link.on_a.clone());
+            registry.add(self.particles[link.b].clone(), link.on_b.clone());
+        }
+        for particle in &self.particles {
+            for generator in generators {
+                registry.add(particle.clone(), generator.clone());
+            }
+        }
+    }
+
+    /// Integrates every particle of the cloth
+    pub fn integrate(&self, duration: Real) {
+        for particle in &self.particles {
+            particle.borrow_mut().integrate(duration);
+        }
+    }
+
+    /// Fixes a particle in place by giving it infinite mass
+    pub fn pin(&self, row: usize, column: usize) {
+        let mut particle = self.particle(row, column).borrow_mut();
+        particle.set_inverse_mass(0.0);
+        particle.set_velocity(0.0, 0.0, 0.0);
+    }
+
+    pub fn unpin(&self, row: usize, column: usize) {
+        self.particle(row, column)
+            .borrow_mut()
+            .set_mass(self.particle_mass);
+    }
+
+    /// Finds springs which have broken since the last check, breaks
+    /// the generator on their other end, removes both from the registry,
+    /// and reports them
+    pub fn take_tears(&mut self, registry: &mut ParticleForceRegistry) -> Vec<ClothTear> {
+        let mut tears = Vec::new();
+        for link in &mut self.links {
+            if link.torn || !(link.on_a.borrow().is_broken() || link.on_b.borrow().is_broken()) {
+                continue;
+            }
+
+            link.torn = true;
+            link.on_a.borrow_mut().break_spring();
+            link.on_b.borrow_mut().break_spring();
+            registry.remove(self.particles[link.a].clone(), link.on_a.clone());
+            registry.remove(self.particles[link.b].clone(), link.on_b.clone());
+            tears.push(ClothTear {
+                a: link.a,
+                b: link.b,
+                kind: link.kind,
+            });
+        }
+        tears
+    }
+
+    /// Returns the number of springs of each kind which are still intact
+    pub fn intact_springs(&self, kind: ClothSpringKind) -> usize {
+        self.links
+            .iter()
+            .filter(|link| link.kind == kind && !link.torn)
+            .count()
+    }
+}
+
+/// Builds a cloth patch, hanging down from its top row by default
+pub struct ClothBuilder {
+    rows: usize,
+    columns: usize,
+    spacing: Real,
+
+    /// The position of the top left particle
+    origin: Vector3,
+
+    /// The directions along each row and down each column
+    across: Vector3,
+    down: Vector3,
+
+    total_mass: Real,
+    particle_damping: Real,
+
+    structural_stiffness: Real,
+    shear_stiffness: Real,
+    bend_stiffness: Real,
+    spring_damping: Real,
+
+    breaking_strain: Option<Real>,
+    pins: Vec<(usize, usize)>,
+}
+
+impl ClothBuilder {
+    pub fn new(rows: usize, columns: usize, spacing: Real) -> Self {
+        assert!(
+            rows >= 2 && columns >= 2,
+            "attempted to build a cloth less than two particles across",
+        );
+
+        Self {
+            rows,
+            columns,
+            spacing,
+            origin: Vector3::default(),
+            across: Vector3::new(1.0, 0.0, 0.0),
+            down: Vector3::new(0.0, -1.0, 0.0),
+            total_mass: 1.0,
+            particle_damping: 0.99,
+            structural_stiffness: 500.0,
+            shear_stiffness: 200.0,
+            bend_stiffness: 50.0,
+            spring_damping: 1.0,
+            breaking_strain: None,
+            pins: Vec::new(),
+        }
+    }
+
+    pub fn origin(mut self, origin: Vector3) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    /// Sets the directions along each row and down each column
+    pub fn axes(mut self, across: Vector3, down: Vector3) -> Self {
+        self.across = across;
+        self.across.normalize();
+        self.down = down;
+        self.down.normalize();
+        self
+    }
+
+    /// Sets the mass of the whole cloth, shared evenly between its particles
+    pub fn mass(mut self, total_mass: Real) -> Self {
+        self.total_mass = total_mass;
+        self
+    }
+
+    pub fn particle_damping(mut self, damping: Real) -> Self {
+        self.particle_damping = damping;
+        self
+    }
+
+    pub fn stiffness(mut self, structural: Real, shear: Real, bend: Real) -> Self {
+        self.structural_stiffness = structural;
+        self.shear_stiffness = shear;
+        self.bend_stiffness = bend;
+        self
+    }
+
+    pub fn spring_damping(mut self, damping: Real) -> Self {
+        self.spring_damping = damping;
+        self
+    }
+
+    /// Makes springs tear once stretched past the given proportion
+    /// of their rest length
+    pub fn breaking_strain(mut self, breaking_strain: Real) -> Self {
+        self.breaking_strain = Some(breaking_strain);
+        self
+    }
+
+    pub fn pin(mut self, row: usize, column: usize) -> Self {
+        self.pins.push((row, column));
+        self
+    }
+
+    pub fn pin_top_corners(self) -> Self {
+        let last = self.columns - 1;
+        self.pin(0, 0).pin(0, last)
+    }
+
+    pub fn pin_top_row(mut self) -> Self {
+        self.pins
+            .extend((0..self.columns).map(|column| (0, column)));
+        self
+    }
+
+    pub fn build(self) -> Cloth {
+        let particle_mass = self.total_mass / (self.rows * self.columns) as Real;
+        let mut particles = Vec::with_capacity(self.rows * self.columns);
+        for row in 0..self.rows {
+            for column in 0..self.columns {
+                let position = self.origin
+                    + self.across * (column as Real * self.spacing)
+                    + self.down * (row as Real * self.spacing);
+                let mut particle = Particle::default();
+                particle.set_position(position.x, position.y, position.z);
+                particle.set_mass(particle_mass);
+                particle.set_damping(self.particle_damping);
+                particles.push(Rc::new(RefCell::new(particle)));
+            }
+        }
+
+        let mut cloth = Cloth {
+            rows: self.rows,
+            columns: self.columns,
+            particles,
+            links: Vec::new(),
+            particle_mass,
+        };
+
+        // Each spring joins a particle to one further along or down the grid
+        let offsets = [
+            (0, 1, ClothSpringKind::Structural, self.structural_stiffness),
+            (1, 0, ClothSpringKind::Structural, self.structural_stiffness),
+            (1, 1, ClothSpringKind::Shear, self.shear_stiffness),
+            (1, -1, ClothSpringKind::Shear, self.shear_stiffness),
+            (0, 2, ClothSpringKind::Bend, self.bend_stiffness),
+            (2, 0, ClothSpringKind::Bend, self.bend_stiffness),
+        ];
+        for row in 0..self.rows {
+            for column in 0..self.columns {
+                for &(row_offset, column_offset, kind, stiffness) in &offsets {
+                    let other_row = row + row_offset;
+                    let other_column = column as isize + column_offset;
+                    if other_row >= self.rows
+                        || other_column < 0
+                        || other_column as usize >= self.columns
+                    {
+                        continue;
+                    }
+
+                    let a = cloth.index(row, column);
+                    let b = cloth.index(other_row, other_column as usize);
+                    cloth
+                        .links
+                        .push(self.link(&cloth.particles, a, b, kind, stiffness));
+                }
+            }
+        }
+
+        for &(row, column) in &self.pins {
+            cloth.pin(row, column);
+        }
+        cloth
+    }
+
+    fn link(
+        &self,
+        particles: &[Rc<RefCell<Particle>>],
+        a: usize,
+        b: usize,
+        kind: ClothSpringKind,
+        stiffness: Real,
+    ) -> ClothLink {
+        let rest_length = (particles[a].borrow().get_position()
+            - particles[b].borrow().get_position())
+        .magnitude();
+        let spring = |other: &Rc<RefCell<Particle>>| {
+            let mut spring = ParticleSpring::with_damping(
+                other.clone(),
+                stiffness,
+                self.spring_damping,
+                rest_length,
+            );
+            spring.set_breaking_strain(self.breaking_strain);
+            Rc::new(RefCell::new(spring))
+        };
+
+        ClothLink {
+            a,
+            b,
+            kind,
+            on_a: spring(&particles[b]),
+            on_b: spring(&particles[a]),
+            torn: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::particle_forces::ParticleGravity;
+
+    #[test]
+    fn builds_every_kind_of_spring() {
+        let cloth = ClothBuilder::new(3, 4, 0.1).build();
+        assert_eq!(cloth.get_particles().len(), 12);
+        assert_eq!(cloth.intact_springs(ClothSpringKind::Structural), 17);
+        assert_eq!(cloth.intact_springs(ClothSpringKind::Shear), 12);
+        assert_eq!(cloth.intact_springs(ClothSpringKind::Bend), 10);
+
+        let corner = cloth.particle(2, 3).borrow().get_position();
+        assert!((corner.x - 0.3).abs() < 1e-6 && (corner.y - -0.2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn pinned_corners_hold_while_the_rest_hangs() {
+        let cloth = ClothBuilder::new(3, 3, 0.1).pin_top_corners().build();
+        let mut registry = ParticleForceRegistry::new();
+        let gravity: Rc<RefCell<dyn ParticleForceGenerator>> = Rc::new(RefCell::new(
+            ParticleGravity::new(Vector3::new(0.0, -9.81, 0.0)),
+        ));
+        cloth.register(&mut registry, &[gravity]);
+
+        for _ in 0..100 {
+            registry.update_forces(0.001);
+            cloth.integrate(0.001);
+        }
+        assert_eq!(cloth.particle(0, 0).borrow().get_position().y, 0.0);
+        assert_eq!(cloth.particle(0, 2).borrow().get_position().y, 0.0);
+        assert!(cloth.particle(2, 1).borrow().get_position().y < -0.2);
+    }
+
+    #[test]
+    fn overstretched_springs_tear_on_both_ends() {
+        let mut cloth = ClothBuilder::new(2, 2, 0.1).breaking_strain(0.5).build();
+        let mut registry = ParticleForceRegistry::new();
+        cloth.register(&mut registry, &[]);
+
+        cloth
+            .particle(1, 1)
+            .borrow_mut()
+            .set_position(0.1, -1.0, 0.0);
+        registry.update_forces(0.001);
+        let tears = cloth.take_tears(&mut registry);
+
+        // Every spring to the moved corner stretches too far
+        assert_eq!(tears.len(), 3);
+        assert!(tears.iter().all(|tear| tear.b == cloth.index(1, 1)));
+        assert_eq!(cloth.intact_springs(ClothSpringKind::Structural), 2);
+        assert_eq!(cloth.intact_springs(ClothSpringKind::Shear), 1);
+        assert!(cloth.take_tears(&mut registry).is_empty());
+    }
+}
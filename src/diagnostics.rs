@@ -0,0 +1,202 @@
+use crate::math::{Real, Vector3};
+use crate::particle_forces::ParticleForceRegistry;
+
+use std::collections::VecDeque;
+
+/// The energy and momentum of the particles in a registry at one step
+#[derive(Clone, Copy, Default)]
+pub struct EnergySample {
+    pub step: usize,
+
+    /// The simulation time (in seconds) at the end of the step
+    pub time: Real,
+
+    pub kinetic_energy: Real,
+
+    /// Only includes generators that report their potential energy
+    pub potential_energy: Real,
+
+    pub linear_momentum: Vector3,
+
+    /// Taken about the origin of the diagnostics that recorded the sample
+    pub angular_momentum: Vector3,
+}
+
+impl EnergySample {
+    pub fn total_energy(&self) -> Real {
+        self.kinetic_energy + self.potential_energy
+    }
+}
+
+/// Records the energy and momentum of a registry's particles each step.
+/// In a system without damping the total energy should stay level,
+/// so a steady rise shows a spring network or step size injecting energy.
+/// Particles with infinite mass carry no kinetic energy or momentum.
+pub struct Diagnostics {
+    /// The point angular momentum is taken about
+    origin: Vector3,
+
+    samples: VecDeque<EnergySample>,
+
+    /// The most samples kept, past which the oldest are dropped
+    capacity: Option<usize>,
+
+    step: usize,
+    time: Real,
+}
+
+impl Diagnostics {
+    pub fn new(origin: Vector3) -> Self {
+        Self {
+            origin,
+            samples: VecDeque::new(),
+            capacity: None,
+            step: 0,
+            time: 0.0,
+        }
+    }
+
+    /// Measures the registry's particles without recording a sample.
+    /// This borrows every particle, so can't be used during an update.
+    pub fn measure(registry: &ParticleForceRegistry, origin: &Vector3) -> EnergySample {
+        let mut sample = EnergySample {
+            potential_energy: registry.potential_energy(),
+            ..EnergySample::default()
+        };
+
+        for particle in registry.particles() {
+            let particle = particle.borrow();
+            if !particle.has_finite_mass() {
+                continue;
+            }
+
+            let mass = particle.get_mass();
+            let velocity = particle.get_velocity();
+            let momentum = velocity * mass;
+
+            sample.kinetic_energy += 0.5 * mass * velocity.square_magnitude();
+            sample.linear_momentum += momentum;
+            sample.angular_momentum += (particle.get_position() - *origin) % momentum;
+        }
+        sample
+    }
+
+    /// Measures the registry after a step of the given duration,
+    /// and records the sample
+    pub fn record(&mut self, registry: &ParticleForceRegistry, duration: Real) -> &EnergySample {
+        self.step += 1;
+        self.time += duration;
+
+        let mut sample = Self::measure(registry, &self.origin);
+        sample.step = self.step;
+        sample.time = self.time;
+
+        if let Some(capacity) = self.capacity {
+            if self.samples.len() >= capacity {
+                self.samples.pop_front();
+            }
+        }
+        self.samples.push_back(sample);
+        &self.samples[self.samples.len() - 1]
+    }
+
+    pub fn get_origin(&self) -> Vector3 {
+        self.origin
+    }
+
+    pub fn set_origin(&mut self, origin: Vector3) {
+        self.origin = origin;
+    }
+
+    /// Returns the kept samples, oldest first
+    pub fn get_samples(&self) -> &VecDeque<EnergySample> {
+        &self.samples
+    }
+
+    pub fn latest(&self) -> Option<&EnergySample> {
+        self.samples.back()
+    }
+
+    /// Returns how far the total energy has moved from the first kept
+    /// sample to the latest, which is positive if energy was gained
+    pub fn energy_drift(&self) -> Real {
+        match (self.samples.front(), self.samples.back()) {
+            (Some(first), Some(last)) => last.total_energy() - first.total_energy(),
+            _ => 0.0,
+        }
+    }
+
+    /// Limits how many samples are kept, or keeps every sample if `None`
+    pub fn set_capacity(&mut self, capacity: Option<usize>) {
+        assert!(capacity != Some(0), "attempted to keep zero energy samples");
+
+        self.capacity = capacity;
+        if let Some(capacity) = capacity {
+            let excess = self.samples.len().saturating_sub(capacity);
+            self.samples.drain(..excess);
+        }
+    }
+
+    /// Discards every sample and restarts the step count and time
+    pub fn clear(&mut self) {
+        self.samples.clear();
+        self.step = 0;
+        self.time = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::particle::ParticleBuilder;
+    use crate::particle_forces::ParticleSpring;
+
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn undamped_spring_pair_conserves_energy_and_momentum() {
+        let particle = |x: Real, vy: Real| {
+            let particle = ParticleBuilder::new()
+                .damping(1.0)
+                .position(Vector3::new(x, 0.0, 0.0))
+                .velocity(Vector3::new(0.0, vy, 0.0))
+                .build();
+            Rc::new(RefCell::new(particle))
+        };
+        let a = particle(-1.5, 0.5);
+        let b = particle(1.5, -0.5);
+
+        let mut registry = ParticleForceRegistry::new();
+        let spring_to = |other: &Rc<RefCell<_>>| {
+            Rc::new(RefCell::new(ParticleSpring::new(other.clone(), 10.0, 2.0)))
+        };
+        registry.add(a.clone(), spring_to(&b));
+        registry.add(b.clone(), spring_to(&a));
+
+        // Stretched by 1 with both particles moving at 0.5
+        let start = Diagnostics::measure(&registry, &Vector3::default());
+        assert!((start.potential_energy - 5.0).abs() < 1e-4);
+        assert!((start.kinetic_energy - 0.25).abs() < 1e-5);
+        assert!((start.angular_momentum.z + 1.5).abs() < 1e-5);
+
+        let mut diagnostics = Diagnostics::new(Vector3::default());
+        diagnostics.set_capacity(Some(100));
+        let duration = 1e-4;
+        for _ in 0..2000 {
+            registry.update_forces(duration);
+            a.borrow_mut().integrate(duration);
+            b.borrow_mut().integrate(duration);
+            diagnostics.record(&registry, duration);
+        }
+
+        assert_eq!(diagnostics.get_samples().len(), 100);
+        assert_eq!(diagnostics.get_samples()[0].step, 1901);
+        let latest = diagnostics.latest().unwrap();
+        assert_eq!(latest.step, 2000);
+        assert!(latest.linear_momentum.magnitude() < 1e-4);
+        assert!((latest.angular_momentum.z + 1.5).abs() < 0.02);
+        let drift = (latest.total_energy() - start.total_energy()) / start.total_energy();
+        assert!(drift.abs() < 0.02, "energy drifted by {}", drift);
+    }
+}
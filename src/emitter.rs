@@ -0,0 +1,368 @@
+use crate::math::{Random, Real, Vector3};
+use crate::particle::Particle;
+use crate::particle_forces::{ParticleForceGenerator, ParticleForceRegistry};
+
+use std::cell::RefCell;
+use std::f32::consts::PI;
+use std::rc::Rc;
+
+/// When an emitter creates particles
+#[derive(Clone, Copy)]
+pub enum Emission {
+    /// A steady stream of the given number of particles per second
+    Rate(Real),
+
+    /// Groups of particles at a fixed interval (in seconds),
+    /// starting with the first update
+    Burst { count: usize, interval: Real },
+
+    /// Only when `ParticleEmitter::burst` is called
+    Manual,
+}
+
+/// A particle created by an emitter, and how long it has left
+struct Emitted {
+    particle: Rc<RefCell<Particle>>,
+    age: Real,
+    lifetime: Real,
+}
+
+/// Creates particles at a point and destroys them when their lifetime ends.
+/// Each particle is launched in a random direction within a cone, with a
+/// random speed, mass and lifetime drawn from the emitter's ranges.
+pub struct ParticleEmitter {
+    position: Vector3,
+    emission: Emission,
+
+    /// The axis of the cone particles are launched within,
+    /// and the angle (in radians) between its axis and edge
+    direction: Vector3,
+    cone_angle: Real,
+
+    speed: (Real, Real),
+    mass: (Real, Real),
+    lifetime: (Real, Real),
+
+    damping: Real,
+    acceleration: Vector3,
+
+    /// Registered on every particle created
+    generators: Vec<Rc<RefCell<dyn ParticleForceGenerator>>>,
+
+    /// The most particles alive at once, past which no more are created
+    max_particles: Option<usize>,
+
+    active: bool,
+    random: Random,
+
+    /// Time carried over between updates towards the next
+    /// particle of a stream or the next burst
+    pending: Real,
+
+    particles: Vec<Emitted>,
+}
+
+impl ParticleEmitter {
+    pub fn new(position: Vector3, emission: Emission, seed: u64) -> Self {
+        check_emission(&emission);
+
+        Self {
+            position,
+            emission,
+            direction: Vector3::new(0.0, 1.0, 0.0),
+            cone_angle: 0.0,
+            speed: (1.0, 1.0),
+            mass: (1.0, 1.0),
+            lifetime: (1.0, 1.0),
+            damping: 0.99,
+            acceleration: Vector3::default(),
+            generators: Vec::new(),
+            max_particles: None,
+            active: true,
+            random: Random::new(seed),
+            pending: 0.0,
+            particles: Vec::new(),
+        }
+    }
+
+    pub fn get_position(&self) -> Vector3 {
+        self.position
+    }
+
+    pub fn set_position(&mut self, position: Vector3) {
+        self.position = position;
+    }
+
+    pub fn set_emission(&mut self, emission: Emission) {
+        check_emission(&emission);
+        self.emission = emission;
+        self.pending = 0.0;
+    }
+
+    /// Sets the cone particles are launched within, by its axis and
+    /// the angle (in radians) between its axis and edge
+    pub fn set_cone(&mut self, direction: Vector3, angle: Real) {
+        assert!(
+            (0.0..=PI).contains(&angle),
+            "attempted to set an emitter cone angle outside [0, pi]",
+        );
+
+        let mut direction = direction;
+        direction.normalize();
+        self.direction = direction;
+        self.cone_angle = angle;
+    }
+
+    pub fn set_speed(&mut self, min: Real, max: Real) {
+        assert!(min <= max, "attempted to set an empty emitter speed range");
+        self.speed = (min, max);
+    }
+
+    pub fn set_mass(&mut self, min: Real, max: Real) {
+        assert!(min <= max, "attempted to set an empty emitter mass range");
+        assert!(min > 0.0, "attempted to set a non-positive emitter mass");
+        self.mass = (min, max);
+    }
+
+    pub fn set_lifetime(&mut self, min: Real, max: Real) {
+        assert!(
+            min <= max,
+            "attempted to set an empty emitter lifetime range"
+        );
+        self.lifetime = (min, max);
+    }
+
+    pub fn set_damping(&mut self, damping: Real) {
+        self.damping = damping;
+    }
+
+    pub fn set_acceleration(&mut self, acceleration: Vector3) {
+        self.acceleration = acceleration;
+    }
+
+    pub fn set_max_particles(&mut self, max_particles: Option<usize>) {
+        self.max_particles = max_particles;
+    }
+
+    /// Adds a generator (e.g. gravity) to be registered on every particle
+    /// created from now on
+    pub fn add_generator(&mut self, generator: Rc<RefCell<dyn ParticleForceGenerator>>) {
+        self.generators.push(generator);
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Starts or stops creating particles,
+    /// leaving those already alive to expire as normal
+    pub fn set_active(&mut self, active: bool) {
+        self.active = active;
+    }
+
+    /// Returns every particle alive
+    pub fn particles(&self) -> impl Iterator<Item = &Rc<RefCell<Particle>>> {
+        self.particles.iter().map(|emitted| &emitted.particle)
+    }
+
+    pub fn live_count(&self) -> usize {
+        self.particles.len()
+    }
+
+    /// Ages every particle, destroying those whose lifetime has ended along
+    /// with their registrations, then creates any particles due. Returns the
+    /// destroyed particles, so that their final state can be used.
+    pub fn update(
+        &mut self,
+        registry: &mut ParticleForceRegistry,
+        duration: Real,
+    ) -> Vec<Rc<RefCell<Particle>>> {
+        let mut expired = Vec::new();
+        self.particles.retain(|emitted| {
+            if emitted.age + duration < emitted.lifetime {
+                return true;
+            }
+            registry.remove_particle(&emitted.particle);
+            expired.push(emitted.particle.clone());
+            false
+        });
+        for emitted in &mut self.particles {
+            emitted.age += duration;
+        }
+
+        if self.active {
+            let due = match self.emission {
+                Emission::Rate(rate) => {
+                    self.pending += duration * rate;
+                    let due = self.pending.floor();
+                    self.pending -= due;
+                    due as usize
+                }
+                Emission::Burst { count, interval } => {
+                    // The first burst is due immediately
+                    let mut due = 0;
+                    self.pending -= duration;
+                    while self.pending <= 0.0 {
+                        due += count;
+                        self.pending += interval;
+                    }
+                    due
+                }
+                Emission::Manual => 0,
+            };
+            self.burst(registry, due);
+        }
+
+        expired
+    }
+
+    /// Creates the given number of particles at once,
+    /// as long as there is room for them
+    pub fn burst(&mut self, registry: &mut ParticleForceRegistry, count: usize) {
+        let room = match self.max_particles {
+            Some(max) => max.saturating_sub(self.particles.len()),
+            None => count,
+        };
+        for _ in 0..count.min(room) {
+            let particle = Rc::new(RefCell::new(self.create_particle()));
+            for generator in &self.generators {
+                registry.add(particle.clone(), generator.clone());
+            }
+            let lifetime = self.random.range(self.lifetime.0, self.lifetime.1);
+            self.particles.push(Emitted {
+                particle,
+                age: 0.0,
+                lifetime,
+            });
+        }
+    }
+
+    /// Integrates every particle alive
+    pub fn integrate(&self, duration: Real) {
+        for emitted in &self.particles {
+            emitted.particle.borrow_mut().integrate(duration);
+        }
+    }
+
+    /// Destroys every particle alive, along with its registrations
+    pub fn clear(&mut self, registry: &mut ParticleForceRegistry) {
+        for emitted in self.particles.drain(..) {
+            registry.remove_particle(&emitted.particle);
+        }
+    }
+
+    fn create_particle(&mut self) -> Particle {
+        let velocity = self.cone_direction() * self.random.range(self.speed.0, self.speed.1);
+
+        let mut particle = Particle::default();
+        particle.set_position(self.position.x, self.position.y, self.position.z);
+        particle.set_velocity(velocity.x, velocity.y, velocity.z);
+        particle.set_acceleration(
+            self.acceleration.x,
+            self.acceleration.y,
+            self.acceleration.z,
+        );
+        particle.set_mass(self.random.range(self.mass.0, self.mass.1));
+        particle.set_damping(self.damping);
+        particle
+    }
+
+    /// Picks a direction uniformly from within the emission cone
+    fn cone_direction(&mut self) -> Vector3 {
+        // Spread evenly over the cap of a unit sphere, whose area
+        // grows linearly with the height it covers
+        let cos_angle = 1.0 - self.random.next_real() * (1.0 - self.cone_angle.cos());
+        let sin_angle = (1.0 - cos_angle.powi(2)).max(0.0).sqrt();
+        let around = 2.0 * PI * self.random.next_real();
+
+        // Two axes perpendicular to the cone's axis
+        let helper = if self.direction.x.abs() < 0.9 {
+            Vector3::new(1.0, 0.0, 0.0)
+        } else {
+            Vector3::new(0.0, 1.0, 0.0)
+        };
+        let mut side = self.direction % helper;
+        side.normalize();
+        let up = side % self.direction;
+
+        self.direction * cos_angle
+            + side * (sin_angle * around.cos())
+            + up * (sin_angle * around.sin())
+    }
+}
+
+fn check_emission(emission: &Emission) {
+    if let Emission::Burst { interval, .. } = emission {
+        assert!(
+            *interval > 0.0,
+            "attempted to emit bursts at a zero or negative interval",
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::particle_forces::ParticleGravity;
+
+    #[test]
+    fn streams_at_the_rate_and_expires_particles() {
+        let mut registry = ParticleForceRegistry::new();
+        let gravity: Rc<RefCell<dyn ParticleForceGenerator>> = Rc::new(RefCell::new(
+            ParticleGravity::new(Vector3::new(0.0, -10.0, 0.0)),
+        ));
+        let mut emitter = ParticleEmitter::new(Vector3::default(), Emission::Rate(20.0), 1);
+        emitter.set_lifetime(0.5, 0.5);
+        emitter.add_generator(gravity.clone());
+
+        let mut expired = 0;
+        for _ in 0..20 {
+            expired += emitter.update(&mut registry, 0.05).len();
+        }
+
+        // Each particle lives for ten updates, and is unregistered when it dies
+        assert_eq!(emitter.live_count(), 10);
+        assert_eq!(expired, 10);
+        assert_eq!(Rc::strong_count(&gravity), 2 + emitter.live_count());
+
+        emitter.clear(&mut registry);
+        assert_eq!(Rc::strong_count(&gravity), 2);
+    }
+
+    #[test]
+    fn bursts_are_capped_by_the_particle_limit() {
+        let mut registry = ParticleForceRegistry::new();
+        let burst = Emission::Burst {
+            count: 4,
+            interval: 1.0,
+        };
+        let mut emitter = ParticleEmitter::new(Vector3::default(), burst, 1);
+        emitter.set_lifetime(10.0, 10.0);
+        emitter.set_max_particles(Some(10));
+
+        emitter.update(&mut registry, 0.1);
+        assert_eq!(emitter.live_count(), 4);
+        emitter.update(&mut registry, 0.5);
+        assert_eq!(emitter.live_count(), 4);
+        for _ in 0..3 {
+            emitter.update(&mut registry, 1.0);
+        }
+        assert_eq!(emitter.live_count(), 10);
+    }
+
+    #[test]
+    fn launches_within_the_cone() {
+        let mut registry = ParticleForceRegistry::new();
+        let mut emitter = ParticleEmitter::new(Vector3::default(), Emission::Manual, 7);
+        emitter.set_cone(Vector3::new(1.0, 0.0, 0.0), 0.3);
+        emitter.set_speed(2.0, 3.0);
+        emitter.burst(&mut registry, 100);
+
+        for particle in emitter.particles() {
+            let velocity = particle.borrow().get_velocity();
+            let speed = velocity.magnitude();
+            assert!((2.0..=3.0).contains(&speed));
+            assert!(velocity.x / speed >= (0.3 as Real).cos() - 1e-5);
+        }
+    }
+}
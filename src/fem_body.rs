@@ -0,0 +1,211 @@
+use crate::math::Real;
+use crate::particle::Particle;
+use crate::particle_forces::{
+    FemMaterial, FemSolver, ParticleFem, ParticleForceGenerator, ParticleForceRegistry,
+};
+use crate::tet_mesh::TetMesh;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// A deformable solid whose corners are particles,
+/// held in shape by finite element forces
+pub struct FemBody {
+    particles: Vec<Rc<RefCell<Particle>>>,
+    fem: Rc<RefCell<ParticleFem>>,
+
+    /// The mass of each particle, restored when it is unpinned
+    masses: Vec<Real>,
+}
+
+impl FemBody {
+    pub fn get_particles(&self) -> &[Rc<RefCell<Particle>>] {
+        &self.particles
+    }
+
+    pub fn get_fem(&self) -> &Rc<RefCell<ParticleFem>> {
+        &self.fem
+    }
+
+    /// Registers the elastic forces, adding further generators
+    /// (e.g. gravity) to every particle
+    pub fn register(
+        &self,
+        registry: &mut ParticleForceRegistry,
+        generators: &[Rc<RefCell<dyn ParticleForceGenerator>>],
+    ) {
+        for particle in &self.particles {
+            registry.add(particle.clone(), self.fem.clone());
+            for generator in generators {
+                registry.add(particle.clone(), generator.clone());
+            }
+        }
+    }
+
+    /// Integrates every particle of the body
+    pub fn integrate(&self, duration: Real) {
+        for particle in &self.particles {
+            particle.borrow_mut().integrate(duration);
+        }
+    }
+
+    /// Fixes a particle in place by giving it infinite mass
+    pub fn pin(&self, index: usize) {
+        let mut particle = self.particles[index].borrow_mut();
+        particle.set_inverse_mass(0.0);
+        particle.set_velocity(0.0, 0.0, 0.0);
+    }
+
+    pub fn unpin(&self, index: usize) {
+        // A corner which belongs to no element has no mass, so stays pinned
+        if self.masses[index] > 0.0 {
+            self.particles[index]
+                .borrow_mut()
+                .set_mass(self.masses[index]);
+        }
+    }
+
+    /// Returns the elastic energy stored in the body at its current shape
+    pub fn elastic_energy(&self) -> Real {
+        self.fem.borrow().elastic_energy()
+    }
+}
+
+/// Builds a deformable solid from a tetrahedral mesh
+pub struct FemBodyBuilder {
+    mesh: TetMesh,
+
+    /// The mass per unit volume, from which each particle's
+    /// share of its surrounding elements' mass is found
+    density: Real,
+
+    material: FemMaterial,
+    solver: FemSolver,
+    particle_damping: Real,
+}
+
+impl FemBodyBuilder {
+    pub fn new(mesh: TetMesh) -> Self {
+        assert!(
+            !mesh.tetrahedra.is_empty(),
+            "attempted to build a body with no tetrahedra",
+        );
+
+        Self {
+            mesh,
+            density: 1000.0,
+            material: FemMaterial::new(1e5, 0.3, 10.0),
+            solver: FemSolver::Corotational,
+            particle_damping: 0.99,
+        }
+    }
+
+    pub fn density(mut self, density: Real) -> Self {
+        self.density = density;
+        self
+    }
+
+    pub fn material(mut self, material: FemMaterial) -> Self {
+        self.material = material;
+        self
+    }
+
+    pub fn solver(mut self, solver: FemSolver) -> Self {
+        self.solver = solver;
+        self
+    }
+
+    pub fn particle_damping(mut self, damping: Real) -> Self {
+        self.particle_damping = damping;
+        self
+    }
+
+    pub fn build(self) -> FemBody {
+        // Share each element's mass equally between its corners
+        let mut masses = vec![0.0; self.mesh.positions.len()];
+        for tetrahedron in &self.mesh.tetrahedra {
+            let corner = |index: usize| self.mesh.positions[tetrahedron[index]];
+            let volume = ((corner(1) - corner(0))
+                * ((corner(2) - corner(0)) % (corner(3) - corner(0))))
+            .abs()
+                / 6.0;
+            for &node in tetrahedron {
+                masses[node] += self.density * volume / 4.0;
+            }
+        }
+
+        let particles: Vec<_> = self
+            .mesh
+            .positions
+            .iter()
+            .zip(masses.iter())
+            .map(|(position, &mass)| {
+                let mut particle = Particle::default();
+                particle.set_position(position.x, position.y, position.z);
+                particle.set_damping(self.particle_damping);
+                if mass > 0.0 {
+                    particle.set_mass(mass);
+                } else {
+                    // A corner which belongs to no element never moves
+                    particle.set_inverse_mass(0.0);
+                }
+                Rc::new(RefCell::new(particle))
+            })
+            .collect();
+
+        let fem = Rc::new(RefCell::new(ParticleFem::new(
+            particles.clone(),
+            &self.mesh.tetrahedra,
+            self.material,
+            self.solver,
+        )));
+
+        FemBody {
+            particles,
+            fem,
+            masses,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Vector3;
+
+    #[test]
+    fn shares_the_mass_of_every_element() {
+        let mesh = TetMesh::cuboid(Vector3::default(), Vector3::new(2.0, 1.0, 1.0), [2, 1, 1]);
+        let body = FemBodyBuilder::new(mesh).density(10.0).build();
+        let mass: Real = body
+            .get_particles()
+            .iter()
+            .map(|particle| particle.borrow().get_mass())
+            .sum();
+        assert!((mass - 20.0).abs() < 1e-4);
+        assert!((body.get_fem().borrow().rest_volume() - 2.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn pinning_and_unpinning_restores_the_mass() {
+        let mesh = TetMesh::cuboid(Vector3::default(), Vector3::new(1.0, 1.0, 1.0), [1, 1, 1]);
+        let body = FemBodyBuilder::new(mesh).build();
+        let mass = body.get_particles()[0].borrow().get_mass();
+
+        body.pin(0);
+        assert!(!body.get_particles()[0].borrow().has_finite_mass());
+        body.unpin(0);
+        assert_eq!(body.get_particles()[0].borrow().get_mass(), mass);
+    }
+
+    #[test]
+    fn corners_outside_every_element_stay_pinned() {
+        let mut mesh = TetMesh::cuboid(Vector3::default(), Vector3::new(1.0, 1.0, 1.0), [1, 1, 1]);
+        mesh.positions.push(Vector3::new(5.0, 0.0, 0.0));
+        let body = FemBodyBuilder::new(mesh).build();
+        let orphan = body.get_particles().len() - 1;
+
+        body.unpin(orphan);
+        assert!(!body.get_particles()[orphan].borrow().has_finite_mass());
+    }
+}
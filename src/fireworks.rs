@@ -0,0 +1,358 @@
+use crate::math::{Random, Real, Vector3};
+use crate::particle::Particle;
+
+/// The acceleration due to gravity felt by every firework
+const GRAVITY: Vector3 = Vector3 {
+    x: 0.0,
+    y: -9.81,
+    z: 0.0,
+};
+
+/// A particle which burns for a while, then bursts into a payload
+/// of other fireworks as described by the rule for its type
+pub struct Firework {
+    particle: Particle,
+
+    /// Which rule created the firework, indexing the rules of a display
+    firework_type: usize,
+
+    /// The time (in seconds) left until the firework bursts
+    fuse: Real,
+}
+
+impl Firework {
+    pub fn get_particle(&self) -> &Particle {
+        &self.particle
+    }
+
+    pub fn get_particle_mut(&mut self) -> &mut Particle {
+        &mut self.particle
+    }
+
+    pub fn get_type(&self) -> usize {
+        self.firework_type
+    }
+
+    pub fn get_fuse(&self) -> Real {
+        self.fuse
+    }
+
+    /// Integrates the firework and burns its fuse, returning whether it
+    /// should now burst, either because the fuse ran out or it hit the ground
+    pub fn update(&mut self, duration: Real) -> bool {
+        self.particle.integrate(duration);
+        self.fuse -= duration;
+        self.fuse < 0.0 || self.particle.get_position().y < 0.0
+    }
+}
+
+/// A number of fireworks of a type created when another bursts
+#[derive(Clone, Copy)]
+pub struct FireworkPayload {
+    pub firework_type: usize,
+    pub count: usize,
+}
+
+/// Describes how fireworks of one type are created and what they burst into
+#[derive(Clone)]
+pub struct FireworkRule {
+    /// The range of time (in seconds) the fuse burns for
+    fuse: (Real, Real),
+
+    /// The range of each component of the velocity, relative to the
+    /// firework this one burst from, if any
+    min_velocity: Vector3,
+    max_velocity: Vector3,
+
+    damping: Real,
+    payloads: Vec<FireworkPayload>,
+}
+
+impl FireworkRule {
+    pub fn new(
+        fuse: (Real, Real),
+        min_velocity: Vector3,
+        max_velocity: Vector3,
+        damping: Real,
+    ) -> Self {
+        assert!(
+            fuse.0 <= fuse.1,
+            "attempted to create a firework rule with an empty fuse range",
+        );
+
+        Self {
+            fuse,
+            min_velocity,
+            max_velocity,
+            damping,
+            payloads: Vec::new(),
+        }
+    }
+
+    /// Adds fireworks of another type to be created when this type bursts
+    pub fn with_payload(mut self, firework_type: usize, count: usize) -> Self {
+        self.payloads.push(FireworkPayload {
+            firework_type,
+            count,
+        });
+        self
+    }
+
+    pub fn get_payloads(&self) -> &[FireworkPayload] {
+        &self.payloads
+    }
+
+    /// Creates a firework of this rule's type at a position, moving with
+    /// a random velocity on top of the given base velocity
+    pub fn create(
+        &self,
+        firework_type: usize,
+        random: &mut Random,
+        position: Vector3,
+        base_velocity: Vector3,
+    ) -> Firework {
+        let velocity = base_velocity
+            + Vector3::new(
+                random.range(self.min_velocity.x, self.max_velocity.x),
+                random.range(self.min_velocity.y, self.max_velocity.y),
+                random.range(self.min_velocity.z, self.max_velocity.z),
+            );
+
+        let mut particle = Particle::default();
+        particle.set_position(position.x, position.y, position.z);
+        particle.set_velocity(velocity.x, velocity.y, velocity.z);
+        particle.set_acceleration(GRAVITY.x, GRAVITY.y, GRAVITY.z);
+        particle.set_mass(1.0);
+        particle.set_damping(self.damping);
+
+        Firework {
+            particle,
+            firework_type,
+            fuse: random.range(self.fuse.0, self.fuse.1),
+        }
+    }
+
+    /// Creates the fireworks a burst firework leaves behind, which start
+    /// where it burst and inherit its velocity
+    pub fn burst(
+        &self,
+        firework: &Firework,
+        rules: &[FireworkRule],
+        random: &mut Random,
+    ) -> Vec<Firework> {
+        let position = firework.particle.get_position();
+        let velocity = firework.particle.get_velocity();
+
+        let mut fireworks = Vec::new();
+        for payload in &self.payloads {
+            if let Some(rule) = rules.get(payload.firework_type) {
+                for _ in 0..payload.count {
+                    fireworks.push(rule.create(payload.firework_type, random, position, velocity));
+                }
+            }
+        }
+        fireworks
+    }
+
+    /// Returns a set of rules making a varied display, indexed by type.
+    /// Types 0, 1, 4 and 6 make good launches from the ground.
+    pub fn classic() -> Vec<FireworkRule> {
+        vec![
+            Self::new(
+                (0.5, 1.4),
+                Vector3::new(-5.0, 25.0, -5.0),
+                Vector3::new(5.0, 28.0, 5.0),
+                0.1,
+            )
+            .with_payload(2, 5)
+            .with_payload(4, 5),
+            Self::new(
+                (0.5, 1.0),
+                Vector3::new(-5.0, 10.0, -5.0),
+                Vector3::new(5.0, 20.0, 5.0),
+                0.8,
+            )
+            .with_payload(3, 2),
+            Self::new(
+                (0.5, 1.5),
+                Vector3::new(-5.0, -5.0, -5.0),
+                Vector3::new(5.0, 5.0, 5.0),
+                0.1,
+            ),
+            Self::new(
+                (0.25, 0.5),
+                Vector3::new(-20.0, 5.0, -5.0),
+                Vector3::new(20.0, 5.0, 5.0),
+                0.2,
+            ),
+            Self::new(
+                (0.5, 1.0),
+                Vector3::new(-20.0, 2.0, -5.0),
+                Vector3::new(20.0, 18.0, 5.0),
+                0.01,
+            )
+            .with_payload(2, 5),
+            Self::new(
+                (3.0, 5.0),
+                Vector3::new(-5.0, 5.0, -5.0),
+                Vector3::new(5.0, 10.0, 5.0),
+                0.95,
+            ),
+            Self::new(
+                (4.0, 5.0),
+                Vector3::new(-5.0, 50.0, -5.0),
+                Vector3::new(5.0, 60.0, 5.0),
+                0.01,
+            )
+            .with_payload(7, 10),
+            Self::new(
+                (0.25, 0.5),
+                Vector3::new(-1.0, -1.0, -1.0),
+                Vector3::new(1.0, 1.0, 1.0),
+                0.01,
+            ),
+            Self::new(
+                (3.0, 5.0),
+                Vector3::new(-15.0, 10.0, -5.0),
+                Vector3::new(15.0, 15.0, 5.0),
+                0.95,
+            ),
+        ]
+    }
+}
+
+/// Holds every live firework of a display, bursting them into their
+/// payloads as their fuses run out
+pub struct FireworkDisplay {
+    rules: Vec<FireworkRule>,
+    fireworks: Vec<Firework>,
+    random: Random,
+}
+
+impl FireworkDisplay {
+    /// Creates an empty display whose firework types index the given rules
+    pub fn new(rules: Vec<FireworkRule>, seed: u64) -> Self {
+        Self {
+            rules,
+            fireworks: Vec::new(),
+            random: Random::new(seed),
+        }
+    }
+
+    pub fn get_rules(&self) -> &[FireworkRule] {
+        &self.rules
+    }
+
+    pub fn get_fireworks(&self) -> &[Firework] {
+        &self.fireworks
+    }
+
+    /// Launches fireworks of a type from a position,
+    /// panicking if there is no rule for the type
+    pub fn launch(&mut self, firework_type: usize, position: Vector3, count: usize) {
+        let rule = self
+            .rules
+            .get(firework_type)
+            .expect("attempted to launch a firework type with no rule");
+        for _ in 0..count {
+            let firework = rule.create(
+                firework_type,
+                &mut self.random,
+                position,
+                Vector3::default(),
+            );
+            self.fireworks.push(firework);
+        }
+    }
+
+    /// Updates every firework, replacing those which burst with their payloads
+    pub fn update(&mut self, duration: Real) {
+        let mut payloads = Vec::new();
+        let mut index = 0;
+        while index < self.fireworks.len() {
+            if !self.fireworks[index].update(duration) {
+                index += 1;
+                continue;
+            }
+
+            let firework = self.fireworks.swap_remove(index);
+            if let Some(rule) = self.rules.get(firework.firework_type) {
+                payloads.extend(rule.burst(&firework, &self.rules, &mut self.random));
+            }
+        }
+        self.fireworks.extend(payloads);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A shell that rises and bursts into three sparks, which then burn out
+    fn rules() -> Vec<FireworkRule> {
+        let up = Vector3::new(0.0, 20.0, 0.0);
+        let still = Vector3::default();
+        vec![
+            FireworkRule::new((1.0, 1.0), up, up, 1.0).with_payload(1, 3),
+            FireworkRule::new((0.5, 0.5), still, still, 1.0),
+        ]
+    }
+
+    #[test]
+    fn bursts_into_payloads_which_burn_out() {
+        let mut display = FireworkDisplay::new(rules(), 3);
+        display.launch(0, Vector3::default(), 1);
+
+        display.update(0.6);
+        assert_eq!(display.get_fireworks().len(), 1);
+        display.update(0.6);
+        let sparks = display.get_fireworks();
+        assert_eq!(sparks.len(), 3);
+        assert!(sparks.iter().all(|spark| spark.get_type() == 1));
+
+        // Sparks start where the shell burst, moving as it was
+        let shell = sparks[0].get_particle();
+        assert!(shell.get_position().y > 10.0);
+        assert!(sparks
+            .iter()
+            .all(|spark| spark.get_particle().get_velocity().y == shell.get_velocity().y));
+
+        display.update(0.6);
+        assert!(display.get_fireworks().is_empty());
+    }
+
+    #[test]
+    fn hitting_the_ground_bursts_early() {
+        let mut rules = rules();
+        rules[0] = FireworkRule::new(
+            (10.0, 10.0),
+            Vector3::new(0.0, -1.0, 0.0),
+            Vector3::new(0.0, -1.0, 0.0),
+            1.0,
+        );
+        let mut display = FireworkDisplay::new(rules, 3);
+        display.launch(0, Vector3::new(0.0, 0.4, 0.0), 2);
+        display.update(0.5);
+        assert!(display.get_fireworks().is_empty());
+    }
+
+    #[test]
+    fn displays_repeat_for_a_seed() {
+        let positions = |seed| {
+            let mut display = FireworkDisplay::new(FireworkRule::classic(), seed);
+            display.launch(0, Vector3::default(), 3);
+            for _ in 0..50 {
+                display.update(0.05);
+            }
+            display
+                .get_fireworks()
+                .iter()
+                .map(|firework| firework.get_particle().get_position())
+                .map(|position| (position.x, position.y, position.z))
+                .collect::<Vec<_>>()
+        };
+        assert!(!positions(5).is_empty());
+        assert_eq!(positions(5), positions(5));
+        assert_ne!(positions(5), positions(6));
+    }
+}
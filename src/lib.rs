@@ -1,6 +1,26 @@
+pub mod attributes;
+pub mod cloth;
+pub mod diagnostics;
+pub mod emitter;
+pub mod fem_body;
+pub mod fireworks;
 pub mod math;
 pub mod particle;
 pub mod particle_forces;
+pub mod rope;
+pub mod soft_body;
+pub mod spatial_hash;
+pub mod tet_mesh;
 
-pub use particle::Particle;
+pub use attributes::Attributes;
+pub use cloth::{Cloth, ClothBuilder};
+pub use diagnostics::{Diagnostics, EnergySample};
+pub use emitter::{Emission, ParticleEmitter};
+pub use fem_body::{FemBody, FemBodyBuilder};
+pub use fireworks::{Firework, FireworkDisplay, FireworkRule};
+pub use particle::{Particle, ParticleBuilder};
 pub use particle_forces::{ParticleForceGenerator, ParticleForceRegistry};
+pub use rope::{Rope, RopeBuilder};
+pub use soft_body::{SoftBody, SoftBodyBuilder};
+pub use spatial_hash::SpatialHash;
+pub use tet_mesh::TetMesh;
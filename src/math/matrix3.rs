@@ -0,0 +1,211 @@
+use crate::math::{Real, Vector3};
+
+use std::ops::{Add, Mul, Sub};
+
+/// Holds a 3x3 matrix in row-major order.
+/// The default constructor creates a zero matrix
+#[derive(Clone, Copy, Default)]
+pub struct Matrix3 {
+    pub data: [Real; 9],
+}
+
+impl Matrix3 {
+    /// Creates a matrix with the given components, row by row
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        m00: Real,
+        m01: Real,
+        m02: Real,
+        m10: Real,
+        m11: Real,
+        m12: Real,
+        m20: Real,
+        m21: Real,
+        m22: Real,
+    ) -> Self {
+        Self {
+            data: [m00, m01, m02, m10, m11, m12, m20, m21, m22],
+        }
+    }
+
+    pub fn identity() -> Self {
+        Self::diagonal(1.0)
+    }
+
+    /// Creates a matrix with the given value along its diagonal and zeros elsewhere
+    pub fn diagonal(value: Real) -> Self {
+        Self::new(value, 0.0, 0.0, 0.0, value, 0.0, 0.0, 0.0, value)
+    }
+
+    /// Creates a matrix whose columns are the given vectors
+    pub fn from_columns(a: Vector3, b: Vector3, c: Vector3) -> Self {
+        Self::new(a.x, b.x, c.x, a.y, b.y, c.y, a.z, b.z, c.z)
+    }
+
+    /// Creates the rotation by the given angle (in radians)
+    /// about the given unit axis
+    pub fn rotation(axis: Vector3, angle: Real) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        let t = 1.0 - cos;
+        let Vector3 { x, y, z } = axis;
+        Self::new(
+            t * x * x + cos,
+            t * x * y - sin * z,
+            t * x * z + sin * y,
+            t * x * y + sin * z,
+            t * y * y + cos,
+            t * y * z - sin * x,
+            t * x * z - sin * y,
+            t * y * z + sin * x,
+            t * z * z + cos,
+        )
+    }
+
+    pub fn get(&self, row: usize, column: usize) -> Real {
+        self.data[row * 3 + column]
+    }
+
+    pub fn column(&self, column: usize) -> Vector3 {
+        Vector3::new(
+            self.data[column],
+            self.data[3 + column],
+            self.data[6 + column],
+        )
+    }
+
+    pub fn transpose(&self) -> Self {
+        let d = &self.data;
+        Self::new(d[0], d[3], d[6], d[1], d[4], d[7], d[2], d[5], d[8])
+    }
+
+    pub fn trace(&self) -> Real {
+        self.data[0] + self.data[4] + self.data[8]
+    }
+
+    pub fn determinant(&self) -> Real {
+        self.column(0) * (self.column(1) % self.column(2))
+    }
+
+    /// Returns the inverse, or `None` if the matrix is singular
+    pub fn inverse(&self) -> Option<Self> {
+        let determinant = self.determinant();
+        if determinant == 0.0 {
+            return None;
+        }
+
+        // The rows of the inverse are the cross products
+        // of pairs of columns, over the determinant
+        let (a, b, c) = (self.column(0), self.column(1), self.column(2));
+        let (r0, r1, r2) = (b % c, c % a, a % b);
+        Some(Self::from_columns(r0, r1, r2).transpose() * (1.0 / determinant))
+    }
+
+    /// Returns the symmetric part, half the sum of the matrix and its transpose
+    pub fn symmetric(&self) -> Self {
+        (*self + self.transpose()) * 0.5
+    }
+
+    /// Multiplies a vector by this matrix
+    pub fn transform(&self, vector: &Vector3) -> Vector3 {
+        let d = &self.data;
+        Vector3::new(
+            d[0] * vector.x + d[1] * vector.y + d[2] * vector.z,
+            d[3] * vector.x + d[4] * vector.y + d[5] * vector.z,
+            d[6] * vector.x + d[7] * vector.y + d[8] * vector.z,
+        )
+    }
+}
+
+/// Multiplies each component by a scalar
+impl Mul<Real> for Matrix3 {
+    type Output = Self;
+
+    fn mul(mut self, rhs: Real) -> Self::Output {
+        for value in &mut self.data {
+            *value *= rhs;
+        }
+        self
+    }
+}
+
+/// Transforms a vector by the matrix
+impl Mul<Vector3> for Matrix3 {
+    type Output = Vector3;
+
+    fn mul(self, rhs: Vector3) -> Self::Output {
+        self.transform(&rhs)
+    }
+}
+
+/// Multiplies two matrices
+impl Mul for Matrix3 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let mut data = [0.0; 9];
+        for row in 0..3 {
+            for column in 0..3 {
+                data[row * 3 + column] =
+                    (0..3).map(|k| self.get(row, k) * rhs.get(k, column)).sum();
+            }
+        }
+        Self { data }
+    }
+}
+
+/// Adds two matrices by adding each component
+impl Add for Matrix3 {
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self::Output {
+        for (value, other) in self.data.iter_mut().zip(rhs.data.iter()) {
+            *value += other;
+        }
+        self
+    }
+}
+
+/// Subtracts two matrices by subtracting each component
+impl Sub for Matrix3 {
+    type Output = Self;
+
+    fn sub(mut self, rhs: Self) -> Self::Output {
+        for (value, other) in self.data.iter_mut().zip(rhs.data.iter()) {
+            *value -= other;
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: &Matrix3, b: &Matrix3) -> bool {
+        a.data
+            .iter()
+            .zip(b.data.iter())
+            .all(|(a, b)| (a - b).abs() < 1e-5)
+    }
+
+    #[test]
+    fn inverse_undoes_the_matrix() {
+        let matrix = Matrix3::new(2.0, 1.0, 0.0, 0.0, 3.0, 1.0, 1.0, 0.0, 4.0);
+        assert!((matrix.determinant() - 25.0).abs() < 1e-5);
+        let inverse = matrix.inverse().unwrap();
+        assert!(close(&(matrix * inverse), &Matrix3::identity()));
+        assert!(Matrix3::diagonal(0.0).inverse().is_none());
+    }
+
+    #[test]
+    fn rotation_turns_about_the_axis() {
+        let rotation = Matrix3::rotation(Vector3::new(0.0, 0.0, 1.0), std::f32::consts::FRAC_PI_2);
+        let turned = rotation * Vector3::new(1.0, 0.0, 0.0);
+        assert!(turned.x.abs() < 1e-6 && (turned.y - 1.0).abs() < 1e-6);
+        assert!(close(
+            &(rotation * rotation.transpose()),
+            &Matrix3::identity()
+        ));
+        assert!((rotation.determinant() - 1.0).abs() < 1e-5);
+    }
+}
@@ -1,5 +1,9 @@
+pub mod matrix3;
 pub mod precision;
+pub mod random;
 pub mod vector3;
 
+pub use matrix3::Matrix3;
 pub use precision::Real;
+pub use random::Random;
 pub use vector3::Vector3;
@@ -0,0 +1,63 @@
+use crate::math::{Real, Vector3};
+
+use std::f32::consts::PI;
+
+/// A small, fast, seeded pseudo-random number generator (xorshift64*).
+/// The same seed always produces the same sequence, so simulations
+/// using it are repeatable.
+#[derive(Clone)]
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        // Scramble the seed with SplitMix64, since xorshift needs a nonzero
+        // state and behaves poorly for seeds with few bits set
+        let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        Self {
+            state: if z == 0 { 1 } else { z },
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Returns a number in the range [0, 1)
+    pub fn next_real(&mut self) -> Real {
+        (self.next_u64() >> 40) as Real / (1u64 << 24) as Real
+    }
+
+    /// Returns a number in the range [min, max)
+    pub fn range(&mut self, min: Real, max: Real) -> Real {
+        min + (max - min) * self.next_real()
+    }
+
+    /// Returns a normally distributed number with mean 0 and variance 1,
+    /// using the Box-Muller transform
+    pub fn gaussian(&mut self) -> Real {
+        let u1 = 1.0 - self.next_real();
+        let u2 = self.next_real();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos()
+    }
+
+    /// Returns a vector whose components are independent standard normal numbers
+    pub fn gaussian_vector(&mut self) -> Vector3 {
+        Vector3::new(self.gaussian(), self.gaussian(), self.gaussian())
+    }
+
+    /// Returns a random unit vector, uniformly distributed over the sphere
+    pub fn unit_vector(&mut self) -> Vector3 {
+        let z = self.range(-1.0, 1.0);
+        let angle = self.range(0.0, 2.0 * PI);
+        let radius = (1.0 - z.powi(2)).sqrt();
+        Vector3::new(radius * angle.cos(), radius * angle.sin(), z)
+    }
+}
@@ -32,7 +32,7 @@ impl Vector3 {
     /// Returns the square of the magnitude of the vector,
     /// using simpler math than getting the magnitude then squaring it
     pub fn square_magnitude(&self) -> Real {
-        self.x.powi(2) + self.y.powi(2) + self.y.powi(2)
+        self.x.powi(2) + self.y.powi(2) + self.z.powi(2)
     }
 
     /// Converts the vector to a unit vector in the same direction
@@ -72,7 +72,7 @@ impl Vector3 {
         Self {
             x: self.y * other.z - self.z * other.y,
             y: self.z * other.x - self.x * other.z,
-            z: self.x * other.y - self.y * other.z,
+            z: self.x * other.y - self.y * other.x,
         }
     }
 }
@@ -163,3 +163,25 @@ impl RemAssign for Vector3 {
         *self = self.vector_product(&rhs);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn square_magnitude_includes_every_axis() {
+        assert_eq!(Vector3::new(1.0, 2.0, 3.0).square_magnitude(), 14.0);
+        assert_eq!(Vector3::new(0.0, 0.0, 2.0).magnitude(), 2.0);
+    }
+
+    #[test]
+    fn vector_product_is_right_handed() {
+        let x = Vector3::new(1.0, 0.0, 0.0);
+        let y = Vector3::new(0.0, 1.0, 0.0);
+        let z = x.vector_product(&y);
+        assert_eq!((z.x, z.y, z.z), (0.0, 0.0, 1.0));
+
+        let product = Vector3::new(1.0, 2.0, 3.0).vector_product(&Vector3::new(4.0, 5.0, 6.0));
+        assert_eq!((product.x, product.y, product.z), (-3.0, 6.0, -3.0));
+    }
+}
@@ -1,5 +1,8 @@
+use crate::attributes::Attributes;
 use crate::math::{Real, Vector3};
 
+use std::any::Any;
+
 /// A simple particle with basic physical properties
 #[derive(Default)]
 pub struct Particle {
@@ -20,6 +23,30 @@ pub struct Particle {
     /// Holds the inverse of the particle's mass.
     /// This simplifies math, and allows for infinite mass
     inverse_mass: Real,
+
+    /// The electric charge, used by electromagnetic force generators
+    /// (0.0 = neutral)
+    charge: Real,
+
+    /// The speed below which the particle counts as resting,
+    /// or `None` if it never sleeps
+    sleep_speed: Option<Real>,
+
+    /// How long (in seconds) the particle must rest before sleeping
+    sleep_delay: Real,
+
+    /// How long the particle has been resting so far
+    rest_time: Real,
+
+    /// The accumulated force a sleeping particle must feel to wake up
+    wake_force: Real,
+
+    /// A sleeping particle is not integrated, and is skipped by
+    /// force registries, until it is woken
+    asleep: bool,
+
+    /// Game data carried by the particle, at most one value of each type
+    attributes: Attributes,
 }
 
 impl Particle {
@@ -31,12 +58,23 @@ impl Particle {
             "attempted to integrate over a zero or negative duration",
         );
 
+        // Forces are only compared once they have all been accumulated,
+        // so ones which cancel out leave the particle asleep
+        if self.asleep {
+            if self.force_accum.square_magnitude() > self.wake_force.powi(2) {
+                self.wake();
+            } else {
+                self.clear_accumulator();
+                return;
+            }
+        }
+
         // Update the position by the linear velocity
         self.position.add_scaled_vector(&self.velocity, duration);
 
         // Update the acceleration by the force
         let mut resultant = self.acceleration;
-        resultant.add_scaled_vector(&self.force_accum, duration);
+        resultant.add_scaled_vector(&self.force_accum, self.inverse_mass);
 
         // Update the velocity by the acceleration
         self.velocity.add_scaled_vector(&resultant, duration);
@@ -46,12 +84,20 @@ impl Particle {
 
         // Clear the force accumulator
         self.clear_accumulator();
+
+        self.track_rest(duration);
     }
 
+    /// Adds a force for the next integration step
     pub fn add_force(&mut self, force: &Vector3) {
         self.force_accum += *force;
     }
 
+    /// Returns the force accumulated so far for the next integration step
+    pub fn get_accumulated_force(&self) -> Vector3 {
+        self.force_accum
+    }
+
     /// Returns the mass, or infinity if the inverse mass is zero
     pub fn get_mass(&self) -> Real {
         if self.inverse_mass == 0.0 {
@@ -111,7 +157,7 @@ impl Particle {
         self.acceleration.z = z;
     }
 
-    pub fn get_damping(&mut self) -> Real {
+    pub fn get_damping(&self) -> Real {
         self.damping
     }
 
@@ -119,9 +165,380 @@ impl Particle {
         self.damping = damping;
     }
 
+    pub fn get_charge(&self) -> Real {
+        self.charge
+    }
+
+    pub fn set_charge(&mut self, charge: Real) {
+        self.charge = charge;
+    }
+
+    pub fn get_attributes(&self) -> &Attributes {
+        &self.attributes
+    }
+
+    pub fn get_attributes_mut(&mut self) -> &mut Attributes {
+        &mut self.attributes
+    }
+
+    /// Returns the particle's attribute of the given type, if it has one
+    pub fn get_attribute<T: Any + Send + Sync>(&self) -> Option<&T> {
+        self.attributes.get()
+    }
+
+    /// Stores an attribute, replacing any existing one of the same type
+    pub fn set_attribute<T: Any + Send + Sync>(&mut self, value: T) {
+        self.attributes.insert(value);
+    }
+
+    /// Lets the particle fall asleep once its speed has stayed
+    /// below the given speed for the given time (in seconds)
+    pub fn set_sleep_threshold(&mut self, speed: Real, delay: Real) {
+        self.sleep_speed = Some(speed);
+        self.sleep_delay = delay;
+    }
+
+    /// Sets the accumulated force above which a sleeping particle wakes
+    /// when integrated (0.0 = any force)
+    pub fn set_wake_force(&mut self, wake_force: Real) {
+        self.wake_force = wake_force;
+    }
+
+    /// Stops the particle from falling asleep, waking it if needed
+    pub fn disable_sleep(&mut self) {
+        self.sleep_speed = None;
+        self.wake();
+    }
+
+    /// Returns the speed below which the particle counts as resting,
+    /// or `None` if it never sleeps
+    pub fn get_sleep_speed(&self) -> Option<Real> {
+        self.sleep_speed
+    }
+
+    pub fn is_asleep(&self) -> bool {
+        self.asleep
+    }
+
+    /// Puts the particle to sleep immediately, stopping it
+    pub fn sleep(&mut self) {
+        self.asleep = true;
+        self.velocity = Vector3::default();
+        self.clear_accumulator();
+    }
+
+    pub fn wake(&mut self) {
+        self.asleep = false;
+        self.rest_time = 0.0;
+    }
+
+    /// Counts how long the particle has been resting,
+    /// and puts it to sleep once it has rested long enough
+    fn track_rest(&mut self, duration: Real) {
+        let speed = match self.sleep_speed {
+            Some(speed) => speed,
+            None => return,
+        };
+
+        if self.velocity.square_magnitude() < speed.powi(2) {
+            self.rest_time += duration;
+            if self.rest_time >= self.sleep_delay {
+                self.sleep();
+            }
+        } else {
+            self.rest_time = 0.0;
+        }
+    }
+
     pub fn clear_accumulator(&mut self) {
         self.force_accum.x = 0.0;
         self.force_accum.y = 0.0;
         self.force_accum.z = 0.0;
     }
 }
+
+/// Builds a particle, starting from a mass of 1 and damping of 0.99
+/// so that it moves sensibly even if only its motion is set
+pub struct ParticleBuilder {
+    position: Vector3,
+    velocity: Vector3,
+    acceleration: Vector3,
+    damping: Real,
+    inverse_mass: Real,
+    charge: Real,
+
+    /// The speed and delay for falling asleep, if the particle may sleep
+    sleep: Option<(Real, Real)>,
+
+    attributes: Attributes,
+}
+
+impl Default for ParticleBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ParticleBuilder {
+    pub fn new() -> Self {
+        Self {
+            position: Vector3::default(),
+            velocity: Vector3::default(),
+            acceleration: Vector3::default(),
+            damping: 0.99,
+            inverse_mass: 1.0,
+            charge: 0.0,
+            sleep: None,
+            attributes: Attributes::new(),
+        }
+    }
+
+    /// A light, fast bullet, which barely drops
+    pub fn pistol() -> Self {
+        Self::new()
+            .mass(2.0)
+            .velocity(Vector3::new(0.0, 0.0, 35.0))
+            .acceleration(Vector3::new(0.0, -1.0, 0.0))
+            .damping(0.99)
+    }
+
+    /// A heavy shell, fired upwards in a high arc
+    pub fn artillery() -> Self {
+        Self::new()
+            .mass(200.0)
+            .velocity(Vector3::new(0.0, 30.0, 40.0))
+            .acceleration(Vector3::new(0.0, -20.0, 0.0))
+            .damping(0.99)
+    }
+
+    /// A slow ball of fire, which floats upwards and quickly slows
+    pub fn fireball() -> Self {
+        Self::new()
+            .mass(1.0)
+            .velocity(Vector3::new(0.0, 0.0, 10.0))
+            .acceleration(Vector3::new(0.0, 0.6, 0.0))
+            .damping(0.9)
+    }
+
+    /// A very fast, nearly massless bolt, which flies dead straight
+    pub fn laser() -> Self {
+        Self::new()
+            .mass(0.1)
+            .velocity(Vector3::new(0.0, 0.0, 100.0))
+            .acceleration(Vector3::new(0.0, 0.0, 0.0))
+            .damping(0.99)
+    }
+
+    pub fn position(mut self, position: Vector3) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub fn velocity(mut self, velocity: Vector3) -> Self {
+        self.velocity = velocity;
+        self
+    }
+
+    pub fn acceleration(mut self, acceleration: Vector3) -> Self {
+        self.acceleration = acceleration;
+        self
+    }
+
+    pub fn damping(mut self, damping: Real) -> Self {
+        self.damping = damping;
+        self
+    }
+
+    /// Sets the mass, panicking if the given value is zero
+    pub fn mass(mut self, mass: Real) -> Self {
+        assert_ne!(mass, 0.0, "attempted to set mass to zero");
+        self.inverse_mass = 1.0 / mass;
+        self
+    }
+
+    /// Gives the particle infinite mass, so that forces cannot move it
+    pub fn infinite_mass(mut self) -> Self {
+        self.inverse_mass = 0.0;
+        self
+    }
+
+    pub fn charge(mut self, charge: Real) -> Self {
+        self.charge = charge;
+        self
+    }
+
+    /// Lets the particle fall asleep once its speed has stayed
+    /// below the given speed for the given time (in seconds)
+    pub fn sleep_threshold(mut self, speed: Real, delay: Real) -> Self {
+        self.sleep = Some((speed, delay));
+        self
+    }
+
+    /// Stores an attribute, replacing any existing one of the same type
+    pub fn attribute<T: Any + Send + Sync>(mut self, value: T) -> Self {
+        self.attributes.insert(value);
+        self
+    }
+
+    pub fn build(self) -> Particle {
+        let mut particle = Particle {
+            position: self.position,
+            velocity: self.velocity,
+            acceleration: self.acceleration,
+            damping: self.damping,
+            inverse_mass: self.inverse_mass,
+            charge: self.charge,
+            attributes: self.attributes,
+            ..Particle::default()
+        };
+        if let Some((speed, delay)) = self.sleep {
+            particle.set_sleep_threshold(speed, delay);
+        }
+        particle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn force_accelerates_by_inverse_mass() {
+        let mut particle = Particle::default();
+        particle.set_mass(2.0);
+        particle.set_damping(1.0);
+        particle.add_force(&Vector3::new(4.0, 0.0, 0.0));
+        particle.integrate(0.5);
+        assert_eq!(particle.get_velocity().x, 1.0);
+
+        // The accumulator is cleared after each step
+        particle.integrate(0.5);
+        assert_eq!(particle.get_velocity().x, 1.0);
+    }
+
+    #[test]
+    fn infinite_mass_ignores_forces() {
+        let mut particle = Particle::default();
+        particle.set_inverse_mass(0.0);
+        particle.set_damping(1.0);
+        particle.add_force(&Vector3::new(4.0, 0.0, 0.0));
+        particle.integrate(0.5);
+        assert_eq!(particle.get_velocity().x, 0.0);
+    }
+
+    fn sleepy_particle() -> Particle {
+        let mut particle = Particle::default();
+        particle.set_mass(1.0);
+        particle.set_damping(1.0);
+        particle.set_sleep_threshold(0.1, 0.5);
+        particle
+    }
+
+    #[test]
+    fn sleeps_after_resting_for_the_delay() {
+        let mut particle = sleepy_particle();
+        particle.set_velocity(0.05, 0.0, 0.0);
+        for _ in 0..4 {
+            particle.integrate(0.1);
+        }
+        assert!(!particle.is_asleep());
+
+        particle.integrate(0.1);
+        assert!(particle.is_asleep());
+        assert_eq!(particle.get_velocity().magnitude(), 0.0);
+    }
+
+    #[test]
+    fn moving_fast_restarts_the_delay() {
+        let mut particle = sleepy_particle();
+        for _ in 0..4 {
+            particle.integrate(0.1);
+        }
+        particle.set_velocity(1.0, 0.0, 0.0);
+        particle.integrate(0.1);
+        particle.set_velocity(0.0, 0.0, 0.0);
+        for _ in 0..4 {
+            particle.integrate(0.1);
+        }
+        assert!(!particle.is_asleep());
+    }
+
+    #[test]
+    fn sleeping_particles_are_not_integrated() {
+        let mut particle = sleepy_particle();
+        particle.set_acceleration(0.0, -10.0, 0.0);
+        particle.sleep();
+        particle.integrate(0.1);
+        assert_eq!(particle.get_position().y, 0.0);
+        assert!(particle.is_asleep());
+    }
+
+    #[test]
+    fn forces_above_the_wake_force_and_calls_wake_particles() {
+        let mut particle = sleepy_particle();
+        particle.set_wake_force(2.0);
+        particle.sleep();
+
+        // Forces which cancel out, or are too weak, leave it asleep
+        particle.add_force(&Vector3::new(5.0, 0.0, 0.0));
+        particle.add_force(&Vector3::new(-5.0, 0.0, 0.0));
+        particle.integrate(0.1);
+        assert!(particle.is_asleep());
+        particle.add_force(&Vector3::new(1.0, 0.0, 0.0));
+        particle.integrate(0.1);
+        assert!(particle.is_asleep());
+        assert_eq!(particle.get_accumulated_force().magnitude(), 0.0);
+
+        particle.add_force(&Vector3::new(3.0, 0.0, 0.0));
+        particle.integrate(0.1);
+        assert!(!particle.is_asleep());
+        assert!((particle.get_velocity().x - 0.3).abs() < 1e-6);
+
+        particle.sleep();
+        particle.wake();
+        assert!(!particle.is_asleep());
+
+        particle.sleep();
+        particle.disable_sleep();
+        for _ in 0..10 {
+            particle.integrate(0.1);
+        }
+        assert!(!particle.is_asleep());
+    }
+
+    /// The mass, velocity, acceleration and damping each shot type was
+    /// given by hand in the ballistic example
+    #[test]
+    fn presets_match_ballistic_example() {
+        let presets = [
+            (ParticleBuilder::pistol(), 2.0, (0.0, 35.0), -1.0, 0.99),
+            (
+                ParticleBuilder::artillery(),
+                200.0,
+                (30.0, 40.0),
+                -20.0,
+                0.99,
+            ),
+            (ParticleBuilder::fireball(), 1.0, (0.0, 10.0), 0.6, 0.9),
+            (ParticleBuilder::laser(), 0.1, (0.0, 100.0), 0.0, 0.99),
+        ];
+        for (builder, mass, (up, forward), gravity, damping) in presets {
+            let particle = builder.build();
+            assert!((particle.get_mass() - mass).abs() < 1e-4);
+            assert_eq!(particle.get_velocity().y, up);
+            assert_eq!(particle.get_velocity().z, forward);
+            assert_eq!(particle.get_acceleration().y, gravity);
+            assert_eq!(particle.get_damping(), damping);
+        }
+    }
+
+    #[test]
+    fn builder_defaults_keep_velocity() {
+        let mut particle = ParticleBuilder::new()
+            .velocity(Vector3::new(1.0, 0.0, 0.0))
+            .build();
+        assert_eq!(particle.get_mass(), 1.0);
+        particle.integrate(0.1);
+        assert!(particle.get_velocity().x > 0.99);
+    }
+}
@@ -0,0 +1,164 @@
+use crate::math::{Real, Vector3};
+
+/// The specific gas constant of dry air (J / (kg K))
+const AIR_GAS_CONSTANT: Real = 287.053;
+
+/// The standard acceleration due to gravity used to build the model
+const STANDARD_GRAVITY: Real = 9.806_65;
+
+/// The standard temperature (K) and pressure (Pa) at sea level
+const STANDARD_TEMPERATURE: Real = 288.15;
+const STANDARD_PRESSURE: Real = 101_325.0;
+
+/// The base altitude (m) and temperature lapse rate (K/m) of each layer
+/// of the International Standard Atmosphere, up to the mesopause
+const LAYERS: [(Real, Real); 7] = [
+    (0.0, 0.0065),
+    (11_000.0, 0.0),
+    (20_000.0, -0.001),
+    (32_000.0, -0.0028),
+    (47_000.0, 0.0),
+    (51_000.0, 0.0028),
+    (71_000.0, 0.002),
+];
+
+/// The altitude above which the model holds the top layer's conditions
+const CEILING: Real = 86_000.0;
+
+/// An ISA-style model of how air temperature, pressure and density
+/// fall with altitude
+#[derive(Clone, Copy)]
+pub struct Atmosphere {
+    /// The y coordinate of sea level
+    sea_level_height: Real,
+
+    /// The temperature (K) and pressure (Pa) of the air at sea level
+    sea_level_temperature: Real,
+    sea_level_pressure: Real,
+}
+
+impl Default for Atmosphere {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+impl Atmosphere {
+    /// Creates the International Standard Atmosphere with sea level at y=0
+    pub fn standard() -> Self {
+        Self::new(0.0, STANDARD_TEMPERATURE, STANDARD_PRESSURE)
+    }
+
+    /// Creates an atmosphere with the given sea level conditions.
+    /// Temperatures at every altitude are offset from the standard
+    /// by the same amount as at sea level.
+    pub fn new(
+        sea_level_height: Real,
+        sea_level_temperature: Real,
+        sea_level_pressure: Real,
+    ) -> Self {
+        Self {
+            sea_level_height,
+            sea_level_temperature,
+            sea_level_pressure,
+        }
+    }
+
+    /// Returns the temperature (K) at the given altitude above sea level
+    pub fn temperature(&self, altitude: Real) -> Real {
+        self.sample(altitude).0
+    }
+
+    /// Returns the pressure (Pa) at the given altitude above sea level
+    pub fn pressure(&self, altitude: Real) -> Real {
+        self.sample(altitude).1
+    }
+
+    /// Returns the density (kg/m^3) at the given altitude above sea level
+    pub fn density(&self, altitude: Real) -> Real {
+        let (temperature, pressure) = self.sample(altitude);
+        pressure / (AIR_GAS_CONSTANT * temperature)
+    }
+
+    /// Returns the density (kg/m^3) at the given point
+    pub fn density_at(&self, point: &Vector3) -> Real {
+        self.density(point.y - self.sea_level_height)
+    }
+
+    /// Returns the ratio of the density at the given point to that at sea level
+    pub fn relative_density_at(&self, point: &Vector3) -> Real {
+        self.density_at(point) / self.density(0.0)
+    }
+
+    /// Returns the speed of sound (m/s) at the given altitude above sea level
+    pub fn speed_of_sound(&self, altitude: Real) -> Real {
+        (1.4 * AIR_GAS_CONSTANT * self.temperature(altitude)).sqrt()
+    }
+
+    /// Finds the temperature and pressure at an altitude by working up
+    /// through the layers, each of which has a constant lapse rate.
+    /// Altitudes outside the modelled range are clamped to it.
+    fn sample(&self, altitude: Real) -> (Real, Real) {
+        let altitude = altitude.clamp(0.0, CEILING);
+        let mut temperature = self.sea_level_temperature;
+        let mut pressure = self.sea_level_pressure;
+
+        for (index, &(base, lapse_rate)) in LAYERS.iter().enumerate() {
+            let top = LAYERS.get(index + 1).map_or(CEILING, |layer| layer.0);
+            let height = altitude.min(top) - base;
+
+            let layer_temperature = temperature - lapse_rate * height;
+            pressure *= if lapse_rate == 0.0 {
+                (-STANDARD_GRAVITY * height / (AIR_GAS_CONSTANT * temperature)).exp()
+            } else {
+                (layer_temperature / temperature)
+                    .powf(STANDARD_GRAVITY / (AIR_GAS_CONSTANT * lapse_rate))
+            };
+            temperature = layer_temperature;
+
+            if altitude <= top {
+                break;
+            }
+        }
+
+        (temperature, pressure)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Densities (kg/m^3) from the International Standard Atmosphere tables
+    const TABLE: [(Real, Real); 7] = [
+        (0.0, 1.2250),
+        (1_000.0, 1.1117),
+        (5_000.0, 0.7364),
+        (11_000.0, 0.3639),
+        (20_000.0, 0.08803),
+        (32_000.0, 0.01322),
+        (47_000.0, 0.001427),
+    ];
+
+    #[test]
+    fn density_matches_standard_table() {
+        let atmosphere = Atmosphere::standard();
+        for &(altitude, density) in &TABLE {
+            let error = (atmosphere.density(altitude) - density).abs() / density;
+            assert!(
+                error < 5e-3,
+                "density at {} m is off by {}",
+                altitude,
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn altitude_is_measured_from_sea_level() {
+        let atmosphere = Atmosphere::new(100.0, STANDARD_TEMPERATURE, STANDARD_PRESSURE);
+        let point = Vector3::new(0.0, 1_100.0, 0.0);
+        assert!((atmosphere.density_at(&point) - atmosphere.density(1_000.0)).abs() < 1e-6);
+        assert_eq!(atmosphere.density(-50.0), atmosphere.density(0.0));
+    }
+}
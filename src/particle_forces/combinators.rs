@@ -0,0 +1,365 @@
+use crate::math::{Real, Vector3};
+use crate::particle::Particle;
+use crate::particle_forces::ParticleForceGenerator;
+
+/// Runs a generator and returns the force it added to the particle
+fn force_from<G: ParticleForceGenerator + ?Sized>(
+    generator: &mut G,
+    particle: &mut Particle,
+    duration: Real,
+) -> Vector3 {
+    let before = particle.get_accumulated_force();
+    generator.update_force(particle, duration);
+    particle.get_accumulated_force() - before
+}
+
+/// Multiplies the force of a generator by a constant factor
+pub struct Scaled<G> {
+    inner: G,
+    factor: Real,
+}
+
+impl<G: ParticleForceGenerator> ParticleForceGenerator for Scaled<G> {
+    fn update_force(&mut self, particle: &mut Particle, duration: Real) {
+        let force = force_from(&mut self.inner, particle, duration);
+        particle.add_force(&(force * (self.factor - 1.0)));
+    }
+
+    fn advance(&mut self, duration: Real) {
+        self.inner.advance(duration);
+    }
+}
+
+impl<G> Scaled<G> {
+    pub fn new(inner: G, factor: Real) -> Self {
+        Self { inner, factor }
+    }
+
+    pub fn set_factor(&mut self, factor: Real) {
+        self.factor = factor;
+    }
+
+    pub fn inner_mut(&mut self) -> &mut G {
+        &mut self.inner
+    }
+}
+
+/// Limits the magnitude of the force of a generator
+pub struct Clamped<G> {
+    inner: G,
+    max_magnitude: Real,
+}
+
+impl<G: ParticleForceGenerator> ParticleForceGenerator for Clamped<G> {
+    fn update_force(&mut self, particle: &mut Particle, duration: Real) {
+        let force = force_from(&mut self.inner, particle, duration);
+        let magnitude = force.magnitude();
+        if magnitude > self.max_magnitude {
+            particle.add_force(&(force * (self.max_magnitude / magnitude - 1.0)));
+        }
+    }
+
+    fn advance(&mut self, duration: Real) {
+        self.inner.advance(duration);
+    }
+}
+
+impl<G> Clamped<G> {
+    pub fn new(inner: G, max_magnitude: Real) -> Self {
+        Self {
+            inner,
+            max_magnitude,
+        }
+    }
+
+    pub fn inner_mut(&mut self) -> &mut G {
+        &mut self.inner
+    }
+}
+
+/// Switches a generator on and off.
+/// A disabled generator still advances, so timed forces stay in step.
+pub struct Toggled<G> {
+    inner: G,
+    enabled: bool,
+}
+
+impl<G: ParticleForceGenerator> ParticleForceGenerator for Toggled<G> {
+    fn update_force(&mut self, particle: &mut Particle, duration: Real) {
+        if self.enabled {
+            self.inner.update_force(particle, duration);
+        }
+    }
+
+    fn advance(&mut self, duration: Real) {
+        self.inner.advance(duration);
+    }
+}
+
+impl<G> Toggled<G> {
+    pub fn new(inner: G, enabled: bool) -> Self {
+        Self { inner, enabled }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn inner_mut(&mut self) -> &mut G {
+        &mut self.inner
+    }
+}
+
+/// Only applies a generator between a start and stop time,
+/// measured from when it was created
+pub struct TimeWindow<G> {
+    inner: G,
+    start: Real,
+    stop: Real,
+    time: Real,
+}
+
+impl<G: ParticleForceGenerator> ParticleForceGenerator for TimeWindow<G> {
+    fn update_force(&mut self, particle: &mut Particle, duration: Real) {
+        if self.time >= self.start && self.time < self.stop {
+            self.inner.update_force(particle, duration);
+        }
+    }
+
+    fn advance(&mut self, duration: Real) {
+        self.time += duration;
+        self.inner.advance(duration);
+    }
+}
+
+impl<G> TimeWindow<G> {
+    /// Creates a window which is open from the start time until the stop time,
+    /// which may be infinite
+    pub fn new(inner: G, start: Real, stop: Real) -> Self {
+        Self {
+            inner,
+            start,
+            stop,
+            time: 0.0,
+        }
+    }
+
+    pub fn get_time(&self) -> Real {
+        self.time
+    }
+
+    pub fn set_time(&mut self, time: Real) {
+        self.time = time;
+    }
+
+    pub fn inner_mut(&mut self) -> &mut G {
+        &mut self.inner
+    }
+}
+
+/// A region of space in which a force can be confined
+#[derive(Clone, Copy)]
+pub enum Region {
+    /// An axis-aligned box between two corners
+    Aabb {
+        min: Vector3,
+        max: Vector3,
+    },
+
+    Sphere {
+        center: Vector3,
+        radius: Real,
+    },
+
+    /// Everything on the side of a plane opposite its unit normal,
+    /// i.e. every point whose projection on the normal is at most the offset
+    HalfSpace {
+        normal: Vector3,
+        offset: Real,
+    },
+}
+
+impl Region {
+    pub fn contains(&self, point: &Vector3) -> bool {
+        match self {
+            Region::Aabb { min, max } => {
+                (min.x..=max.x).contains(&point.x)
+                    && (min.y..=max.y).contains(&point.y)
+                    && (min.z..=max.z).contains(&point.z)
+            }
+            Region::Sphere { center, radius } => {
+                (*point - *center).square_magnitude() <= radius.powi(2)
+            }
+            Region::HalfSpace { normal, offset } => *point * *normal <= *offset,
+        }
+    }
+}
+
+/// Only applies a generator to particles inside a region
+pub struct WithinRegion<G> {
+    inner: G,
+    region: Region,
+}
+
+impl<G: ParticleForceGenerator> ParticleForceGenerator for WithinRegion<G> {
+    fn update_force(&mut self, particle: &mut Particle, duration: Real) {
+        if self.region.contains(&particle.get_position()) {
+            self.inner.update_force(particle, duration);
+        }
+    }
+
+    fn advance(&mut self, duration: Real) {
+        self.inner.advance(duration);
+    }
+}
+
+impl<G> WithinRegion<G> {
+    pub fn new(inner: G, region: Region) -> Self {
+        Self { inner, region }
+    }
+
+    pub fn set_region(&mut self, region: Region) {
+        self.region = region;
+    }
+
+    pub fn inner_mut(&mut self) -> &mut G {
+        &mut self.inner
+    }
+}
+
+/// Applies several generators as one
+#[derive(Default)]
+pub struct ForceSum {
+    generators: Vec<Box<dyn ParticleForceGenerator>>,
+}
+
+impl ParticleForceGenerator for ForceSum {
+    fn update_force(&mut self, particle: &mut Particle, duration: Real) {
+        for generator in &mut self.generators {
+            generator.update_force(particle, duration);
+        }
+    }
+
+    fn advance(&mut self, duration: Real) {
+        for generator in &mut self.generators {
+            generator.advance(duration);
+        }
+    }
+}
+
+impl ForceSum {
+    pub fn new() -> Self {
+        Self {
+            generators: Vec::new(),
+        }
+    }
+
+    pub fn add(&mut self, generator: Box<dyn ParticleForceGenerator>) {
+        self.generators.push(generator);
+    }
+
+    /// Adds a generator, returning the sum for chaining
+    pub fn with<G: ParticleForceGenerator + 'static>(mut self, generator: G) -> Self {
+        self.add(Box::new(generator));
+        self
+    }
+}
+
+/// Methods for composing generators from existing ones
+pub trait ParticleForceGeneratorExt: ParticleForceGenerator + Sized {
+    fn scaled(self, factor: Real) -> Scaled<Self> {
+        Scaled::new(self, factor)
+    }
+
+    fn clamped(self, max_magnitude: Real) -> Clamped<Self> {
+        Clamped::new(self, max_magnitude)
+    }
+
+    fn toggled(self, enabled: bool) -> Toggled<Self> {
+        Toggled::new(self, enabled)
+    }
+
+    fn between(self, start: Real, stop: Real) -> TimeWindow<Self> {
+        TimeWindow::new(self, start, stop)
+    }
+
+    fn within(self, region: Region) -> WithinRegion<Self> {
+        WithinRegion::new(self, region)
+    }
+
+    fn plus<G: ParticleForceGenerator + 'static>(self, other: G) -> ForceSum
+    where
+        Self: 'static,
+    {
+        ForceSum::new().with(self).with(other)
+    }
+}
+
+impl<G: ParticleForceGenerator> ParticleForceGeneratorExt for G {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::particle_forces::ParticleGravity;
+
+    fn unit_mass() -> Particle {
+        let mut particle = Particle::default();
+        particle.set_mass(1.0);
+        particle
+    }
+
+    fn down(strength: Real) -> ParticleGravity {
+        ParticleGravity::new(Vector3::new(0.0, -strength, 0.0))
+    }
+
+    #[test]
+    fn wrappers_leave_earlier_forces_alone() {
+        let mut particle = unit_mass();
+        particle.add_force(&Vector3::new(1.0, 0.0, 0.0));
+
+        down(10.0)
+            .scaled(2.0)
+            .clamped(5.0)
+            .update_force(&mut particle, 0.1);
+        let force = particle.get_accumulated_force();
+        assert!((force.x - 1.0).abs() < 1e-6 && (force.y - -5.0).abs() < 1e-6);
+
+        down(10.0).scaled(0.5).update_force(&mut particle, 0.1);
+        assert!((particle.get_accumulated_force().y - -10.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn time_window_opens_and_closes() {
+        let mut windowed = down(1.0).between(1.0, 2.0);
+        let mut particle = unit_mass();
+        let mut applied = Vec::new();
+        for _ in 0..6 {
+            particle.clear_accumulator();
+            windowed.update_force(&mut particle, 0.5);
+            windowed.advance(0.5);
+            applied.push(particle.get_accumulated_force().y != 0.0);
+        }
+        assert_eq!(applied, vec![false, false, true, true, false, false]);
+    }
+
+    #[test]
+    fn region_and_toggle_gate_the_force() {
+        let region = Region::Sphere {
+            center: Vector3::default(),
+            radius: 1.0,
+        };
+        let mut sum = down(1.0).within(region).plus(down(2.0).toggled(false));
+        let mut particle = unit_mass();
+        sum.update_force(&mut particle, 0.1);
+        assert!((particle.get_accumulated_force().y - -1.0).abs() < 1e-6);
+
+        particle.clear_accumulator();
+        particle.set_position(2.0, 0.0, 0.0);
+        sum.update_force(&mut particle, 0.1);
+        assert_eq!(particle.get_accumulated_force().y, 0.0);
+    }
+}
@@ -0,0 +1,37 @@
+use crate::math::{Real, Vector3};
+
+/// An electric and magnetic field which may vary over space and time
+pub trait ElectromagneticField {
+    fn electric(&self, point: &Vector3, time: Real) -> Vector3;
+    fn magnetic(&self, point: &Vector3, time: Real) -> Vector3;
+}
+
+/// Electric and magnetic fields which are the same everywhere
+pub struct UniformField {
+    electric: Vector3,
+    magnetic: Vector3,
+}
+
+impl ElectromagneticField for UniformField {
+    fn electric(&self, _point: &Vector3, _time: Real) -> Vector3 {
+        self.electric
+    }
+
+    fn magnetic(&self, _point: &Vector3, _time: Real) -> Vector3 {
+        self.magnetic
+    }
+}
+
+impl UniformField {
+    pub fn new(electric: Vector3, magnetic: Vector3) -> Self {
+        Self { electric, magnetic }
+    }
+
+    pub fn electric(electric: Vector3) -> Self {
+        Self::new(electric, Vector3::default())
+    }
+
+    pub fn magnetic(magnetic: Vector3) -> Self {
+        Self::new(Vector3::default(), magnetic)
+    }
+}
@@ -0,0 +1,134 @@
+use crate::math::{Real, Vector3};
+
+/// The speed below which a sliding particle may be held by static friction
+const STATIC_SPEED: Real = 0.01;
+
+/// The Coulomb friction coefficients of a pair of surfaces in contact
+#[derive(Clone, Copy)]
+pub struct FrictionMaterial {
+    /// The ratio of the largest tangential force that can be resisted
+    /// without slipping to the normal force
+    static_friction: Real,
+
+    /// The ratio of the tangential force resisting sliding to the normal force
+    kinetic_friction: Real,
+}
+
+impl FrictionMaterial {
+    pub fn new(static_friction: Real, kinetic_friction: Real) -> Self {
+        assert!(
+            static_friction >= kinetic_friction,
+            "attempted to create a material with less static than kinetic friction",
+        );
+        Self {
+            static_friction,
+            kinetic_friction,
+        }
+    }
+
+    pub fn ice() -> Self {
+        Self::new(0.1, 0.03)
+    }
+
+    pub fn wood() -> Self {
+        Self::new(0.5, 0.3)
+    }
+
+    pub fn steel() -> Self {
+        Self::new(0.75, 0.55)
+    }
+
+    pub fn rubber() -> Self {
+        Self::new(1.0, 0.8)
+    }
+
+    /// Combines the coefficients of two materials touching one another
+    /// by taking their geometric mean
+    pub fn combine(&self, other: &FrictionMaterial) -> Self {
+        Self {
+            static_friction: (self.static_friction * other.static_friction).sqrt(),
+            kinetic_friction: (self.kinetic_friction * other.kinetic_friction).sqrt(),
+        }
+    }
+
+    pub fn get_static_friction(&self) -> Real {
+        self.static_friction
+    }
+
+    pub fn get_kinetic_friction(&self) -> Real {
+        self.kinetic_friction
+    }
+
+    /// Calculates the friction force on a particle at a contact over the next step,
+    /// given its velocity and the other forces along the contact surface,
+    /// and the magnitude of the force pressing it onto the surface.
+    /// A particle is held in place while static friction can resist the other
+    /// forces, and friction never reverses a particle's sliding direction.
+    pub fn friction_force(
+        &self,
+        tangential_velocity: Vector3,
+        tangential_force: Vector3,
+        normal_force: Real,
+        mass: Real,
+        duration: Real,
+    ) -> Vector3 {
+        if normal_force <= 0.0 {
+            return Vector3::default();
+        }
+
+        // The force which would stop the particle sliding over this step
+        let stopping = tangential_velocity * (-mass / duration) - tangential_force;
+        let stopping_magnitude = stopping.magnitude();
+
+        let speed = tangential_velocity.magnitude();
+        let limit = if speed <= STATIC_SPEED {
+            self.static_friction * normal_force
+        } else {
+            self.kinetic_friction * normal_force
+        };
+        if stopping_magnitude <= limit {
+            return stopping;
+        }
+
+        // Otherwise the particle slides, with kinetic friction opposing the motion
+        let magnitude = self.kinetic_friction * normal_force;
+        if speed > 0.0 {
+            tangential_velocity * (-magnitude / speed)
+        } else {
+            stopping * (magnitude / stopping_magnitude)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn static_friction_holds_against_small_forces() {
+        let material = FrictionMaterial::new(0.5, 0.3);
+        let push = Vector3::new(4.0, 0.0, 0.0);
+        let friction = material.friction_force(Vector3::default(), push, 10.0, 1.0, 0.1);
+        assert!((friction + push).magnitude() < 1e-6);
+    }
+
+    #[test]
+    fn kinetic_friction_opposes_sliding() {
+        let material = FrictionMaterial::new(0.5, 0.3);
+        let velocity = Vector3::new(0.0, 0.0, 5.0);
+        let friction = material.friction_force(velocity, Vector3::default(), 10.0, 1.0, 0.1);
+        assert!((friction.z - -3.0).abs() < 1e-6 && friction.x == 0.0);
+
+        // A slow particle is stopped rather than pushed backwards
+        let velocity = Vector3::new(0.0, 0.0, 0.1);
+        let friction = material.friction_force(velocity, Vector3::default(), 10.0, 1.0, 0.1);
+        assert!((friction.z - -1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn combining_takes_the_geometric_mean() {
+        let combined = FrictionMaterial::new(0.9, 0.4).combine(&FrictionMaterial::new(0.1, 0.1));
+        assert!((combined.get_static_friction() - 0.3).abs() < 1e-6);
+        assert!((combined.get_kinetic_friction() - 0.2).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,177 @@
+use crate::math::{Real, Vector3};
+use crate::particle_forces::LiquidSurface;
+
+use std::f32::consts::PI;
+
+/// The acceleration due to gravity used for the deep water dispersion relation
+const WAVE_GRAVITY: Real = 9.81;
+
+/// The number of iterations used to find which surface point lies above a position
+const SURFACE_ITERATIONS: usize = 4;
+
+/// A single travelling Gerstner (trochoidal) wave
+#[derive(Clone, Copy)]
+pub struct GerstnerWave {
+    /// The horizontal unit direction in which the wave travels
+    direction: Vector3,
+    amplitude: Real,
+
+    /// The spatial frequency of the wave (2π / wavelength)
+    wave_number: Real,
+
+    /// The temporal frequency of the wave, from the deep water dispersion relation
+    angular_frequency: Real,
+
+    /// How sharp the crests are
+    /// (0.0 = sine wave, 1.0 = crests just about to form loops)
+    steepness: Real,
+}
+
+impl GerstnerWave {
+    /// Creates a wave travelling along the horizontal part of the given direction
+    pub fn new(direction: Vector3, amplitude: Real, wavelength: Real, steepness: Real) -> Self {
+        assert!(
+            wavelength > 0.0,
+            "attempted to create a wave with no wavelength"
+        );
+
+        let mut direction = Vector3::new(direction.x, 0.0, direction.z);
+        direction.normalize();
+        let wave_number = 2.0 * PI / wavelength;
+        Self {
+            direction,
+            amplitude,
+            wave_number,
+            angular_frequency: (WAVE_GRAVITY * wave_number).sqrt(),
+            steepness,
+        }
+    }
+
+    fn phase(&self, rest_position: &Vector3, time: Real) -> Real {
+        self.wave_number * (self.direction * *rest_position) - self.angular_frequency * time
+    }
+
+    /// Returns how far the surface point with the given rest position is moved
+    fn displacement(&self, rest_position: &Vector3, time: Real) -> Vector3 {
+        let phase = self.phase(rest_position, time);
+        let horizontal = self.steepness / self.wave_number * phase.cos();
+        Vector3::new(
+            self.direction.x * horizontal,
+            self.amplitude * phase.sin(),
+            self.direction.z * horizontal,
+        )
+    }
+}
+
+/// A liquid surface made from a sum of Gerstner waves about a mean height
+pub struct GerstnerWaves {
+    /// The height of the still surface above y=0
+    mean_height: Real,
+    waves: Vec<GerstnerWave>,
+}
+
+impl LiquidSurface for GerstnerWaves {
+    fn depth(&self, point: &Vector3, time: Real) -> Real {
+        self.height(point.x, point.z, time) - point.y
+    }
+
+    fn normal(&self, point: &Vector3, time: Real) -> Vector3 {
+        // Take central differences over a small fraction of the shortest wavelength
+        let step = self
+            .waves
+            .iter()
+            .map(|wave| 0.01 / wave.wave_number)
+            .fold(0.01, Real::min);
+        let slope_x = (self.height(point.x + step, point.z, time)
+            - self.height(point.x - step, point.z, time))
+            / (2.0 * step);
+        let slope_z = (self.height(point.x, point.z + step, time)
+            - self.height(point.x, point.z - step, time))
+            / (2.0 * step);
+
+        let mut normal = Vector3::new(-slope_x, 1.0, -slope_z);
+        normal.normalize();
+        normal
+    }
+}
+
+impl GerstnerWaves {
+    pub fn new(mean_height: Real) -> Self {
+        Self {
+            mean_height,
+            waves: Vec::new(),
+        }
+    }
+
+    pub fn add_wave(&mut self, wave: GerstnerWave) {
+        self.waves.push(wave);
+    }
+
+    /// Returns the height of the surface above the given horizontal position.
+    /// Gerstner waves move surface points sideways, so the point which ends up
+    /// above the position is found by fixed-point iteration.
+    pub fn height(&self, x: Real, z: Real, time: Real) -> Real {
+        let target = Vector3::new(x, 0.0, z);
+        let mut rest_position = target;
+        for _ in 0..SURFACE_ITERATIONS {
+            let offset = self.displacement(&rest_position, time);
+            rest_position = target - Vector3::new(offset.x, 0.0, offset.z);
+        }
+        self.mean_height + self.displacement(&rest_position, time).y
+    }
+
+    fn displacement(&self, rest_position: &Vector3, time: Real) -> Vector3 {
+        self.waves.iter().fold(Vector3::default(), |total, wave| {
+            total + wave.displacement(rest_position, time)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn single_wave(steepness: Real) -> GerstnerWaves {
+        let mut waves = GerstnerWaves::new(2.0);
+        waves.add_wave(GerstnerWave::new(
+            Vector3::new(1.0, 0.0, 0.0),
+            0.5,
+            10.0,
+            steepness,
+        ));
+        waves
+    }
+
+    #[test]
+    fn flat_waves_are_sine_waves() {
+        let waves = single_wave(0.0);
+        for i in 0..20 {
+            let x = i as Real * 0.7;
+            let expected = 2.0 + 0.5 * (2.0 * PI / 10.0 * x).sin();
+            assert!((waves.height(x, 3.0, 0.0) - expected).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn steep_waves_stay_within_their_amplitude() {
+        let waves = single_wave(0.5);
+        let heights: Vec<_> = (0..100)
+            .map(|i| waves.height(i as Real * 0.1, 0.0, 0.0))
+            .collect();
+        let highest = heights.iter().cloned().fold(Real::MIN, Real::max);
+        let lowest = heights.iter().cloned().fold(Real::MAX, Real::min);
+        assert!((highest - 2.5).abs() < 0.02 && (lowest - 1.5).abs() < 0.02);
+    }
+
+    #[test]
+    fn depth_and_normal_follow_the_surface() {
+        let waves = single_wave(0.0);
+        let point = Vector3::new(0.0, 1.0, 0.0);
+        assert!((waves.depth(&point, 0.0) - 1.0).abs() < 1e-5);
+
+        // At x=0 the sine rises towards +x, so the normal leans back
+        let normal = waves.normal(&point, 0.0);
+        assert!((normal.magnitude() - 1.0).abs() < 1e-4);
+        assert!(normal.x < 0.0 && normal.y > 0.0 && normal.z.abs() < 1e-4);
+    }
+}
@@ -0,0 +1,50 @@
+use crate::math::{Real, Vector3};
+
+/// The boundary between a liquid and the space above it
+pub trait LiquidSurface {
+    /// Returns how far the point lies below the surface at the given time,
+    /// or a negative distance if it lies above
+    fn depth(&self, point: &Vector3, time: Real) -> Real;
+
+    /// Returns the unit normal of the surface near the point, pointing out of the liquid
+    fn normal(&self, point: &Vector3, time: Real) -> Vector3;
+}
+
+/// A flat, still liquid surface with any orientation
+pub struct LiquidPlane {
+    /// The unit normal of the plane, pointing out of the liquid
+    normal: Vector3,
+
+    /// The distance of the plane from the origin along its normal
+    offset: Real,
+}
+
+impl LiquidSurface for LiquidPlane {
+    fn depth(&self, point: &Vector3, _time: Real) -> Real {
+        self.offset - *point * self.normal
+    }
+
+    fn normal(&self, _point: &Vector3, _time: Real) -> Vector3 {
+        self.normal
+    }
+}
+
+impl LiquidPlane {
+    /// Creates a plane through the given point with the given normal
+    pub fn new(normal: Vector3, point: Vector3) -> Self {
+        let mut normal = normal;
+        normal.normalize();
+        Self {
+            normal,
+            offset: point * normal,
+        }
+    }
+
+    /// Creates a horizontal plane at the given height above y=0
+    pub fn horizontal(height: Real) -> Self {
+        Self {
+            normal: Vector3::new(0.0, 1.0, 0.0),
+            offset: height,
+        }
+    }
+}
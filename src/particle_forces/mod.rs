@@ -1,19 +1,77 @@
+pub mod atmosphere;
+pub mod combinators;
+pub mod electromagnetic_field;
+pub mod friction;
+pub mod gerstner_waves;
+pub mod liquid_surface;
+pub mod pair_potential;
+pub mod particle_aerodynamics;
 pub mod particle_anchored_spring;
+pub mod particle_brownian;
 pub mod particle_bungee;
 pub mod particle_buoyancy;
+pub mod particle_coulomb;
 pub mod particle_drag;
 pub mod particle_fake_spring;
+pub mod particle_fem;
+pub mod particle_flock;
 pub mod particle_force_generator;
 pub mod particle_force_registry;
+pub mod particle_gas_pressure;
+pub mod particle_granular;
 pub mod particle_gravity;
+pub(crate) mod particle_group;
+pub mod particle_impulse;
+pub mod particle_langevin;
+pub mod particle_lorentz;
+pub mod particle_pair_forces;
+pub mod particle_pid;
+pub mod particle_plane_friction;
+pub mod particle_rod;
+pub mod particle_sph;
 pub mod particle_spring;
+pub mod particle_steering;
+pub mod particle_thruster;
+pub mod particle_timed_force;
+pub mod periodic_box;
+pub mod sph_kernels;
+pub mod spring_damping;
 
+pub use atmosphere::Atmosphere;
+pub use combinators::{
+    Clamped, ForceSum, ParticleForceGeneratorExt, Region, Scaled, TimeWindow, Toggled, WithinRegion,
+};
+pub use electromagnetic_field::{ElectromagneticField, UniformField};
+pub use friction::FrictionMaterial;
+pub use gerstner_waves::{GerstnerWave, GerstnerWaves};
+pub use liquid_surface::{LiquidPlane, LiquidSurface};
+pub use pair_potential::PairPotential;
+pub use particle_aerodynamics::ParticleAerodynamics;
 pub use particle_anchored_spring::ParticleAnchoredSpring;
+pub use particle_brownian::ParticleBrownian;
 pub use particle_bungee::ParticleBungee;
 pub use particle_buoyancy::ParticleBuoyancy;
+pub use particle_coulomb::ParticleCoulomb;
 pub use particle_drag::ParticleDrag;
 pub use particle_fake_spring::ParticleFakeSpring;
+pub use particle_fem::{FemMaterial, FemSolver, ParticleFem};
+pub use particle_flock::ParticleFlock;
 pub use particle_force_generator::ParticleForceGenerator;
 pub use particle_force_registry::ParticleForceRegistry;
+pub use particle_gas_pressure::ParticleGasPressure;
+pub use particle_granular::{GranularMaterial, ParticleGranular};
 pub use particle_gravity::ParticleGravity;
+pub use particle_impulse::ParticleImpulse;
+pub use particle_langevin::ParticleLangevin;
+pub use particle_lorentz::ParticleLorentz;
+pub use particle_pair_forces::ParticlePairForces;
+pub use particle_pid::{ParticlePid, PidGains, PidTarget};
+pub use particle_plane_friction::ParticlePlaneFriction;
+pub use particle_rod::ParticleRod;
+pub use particle_sph::ParticleSph;
 pub use particle_spring::ParticleSpring;
+pub use particle_steering::{ParticleSteering, SteeringBehavior};
+pub use particle_thruster::{ParticleThruster, ThrustDirection};
+pub use particle_timed_force::{ForceCurve, Interpolation, ParticleTimedForce};
+pub use periodic_box::PeriodicBox;
+pub use spring_damping::SpringDamping;
@@ -0,0 +1,118 @@
+use crate::math::Real;
+
+/// A potential energy between two particles depending only on their distance
+#[derive(Clone, Copy)]
+pub enum PairPotential {
+    /// 4ε((σ/r)^12 - (σ/r)^6): strong repulsion up close
+    /// and weak attraction further out, as between neutral atoms
+    LennardJones { epsilon: Real, sigma: Real },
+
+    /// D(1 - e^(-a(r - r0)))^2 - D: a chemical bond of depth D
+    /// and stiffness a, resting at r0
+    Morse {
+        depth: Real,
+        stiffness: Real,
+        equilibrium: Real,
+    },
+
+    /// ε(σ/r)^n: purely repulsive, for soft balls that only push
+    SoftSphere {
+        epsilon: Real,
+        sigma: Real,
+        exponent: i32,
+    },
+}
+
+impl PairPotential {
+    /// Returns the potential energy at the given separation
+    pub fn energy(&self, distance: Real) -> Real {
+        match *self {
+            PairPotential::LennardJones { epsilon, sigma } => {
+                let ratio6 = (sigma / distance).powi(6);
+                4.0 * epsilon * (ratio6.powi(2) - ratio6)
+            }
+            PairPotential::Morse {
+                depth,
+                stiffness,
+                equilibrium,
+            } => {
+                let decay = (-stiffness * (distance - equilibrium)).exp();
+                depth * (1.0 - decay).powi(2) - depth
+            }
+            PairPotential::SoftSphere {
+                epsilon,
+                sigma,
+                exponent,
+            } => epsilon * (sigma / distance).powi(exponent),
+        }
+    }
+
+    /// Returns the force at the given separation,
+    /// positive when the particles are pushed apart
+    pub fn force(&self, distance: Real) -> Real {
+        match *self {
+            PairPotential::LennardJones { epsilon, sigma } => {
+                let ratio6 = (sigma / distance).powi(6);
+                24.0 * epsilon * (2.0 * ratio6.powi(2) - ratio6) / distance
+            }
+            PairPotential::Morse {
+                depth,
+                stiffness,
+                equilibrium,
+            } => {
+                let decay = (-stiffness * (distance - equilibrium)).exp();
+                -2.0 * depth * stiffness * (1.0 - decay) * decay
+            }
+            PairPotential::SoftSphere {
+                epsilon,
+                sigma,
+                exponent,
+            } => exponent as Real * epsilon * (sigma / distance).powi(exponent) / distance,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn force_is_the_slope_of_the_energy() {
+        let potentials = [
+            PairPotential::LennardJones {
+                epsilon: 1.0,
+                sigma: 1.0,
+            },
+            PairPotential::Morse {
+                depth: 2.0,
+                stiffness: 1.5,
+                equilibrium: 1.2,
+            },
+            PairPotential::SoftSphere {
+                epsilon: 1.0,
+                sigma: 1.0,
+                exponent: 12,
+            },
+        ];
+        let step = 1e-3;
+        for potential in &potentials {
+            for &distance in &[1.0, 1.3, 2.0] {
+                let slope = (potential.energy(distance + step) - potential.energy(distance - step))
+                    / (2.0 * step);
+                let force = potential.force(distance);
+                assert!((force + slope).abs() < 1e-2 * (1.0 + force.abs()));
+            }
+        }
+    }
+
+    #[test]
+    fn lennard_jones_rests_at_its_minimum() {
+        let potential = PairPotential::LennardJones {
+            epsilon: 1.0,
+            sigma: 1.0,
+        };
+        let minimum = (2.0 as Real).powf(1.0 / 6.0);
+        assert!(potential.force(minimum).abs() < 1e-4);
+        assert!((potential.energy(minimum) - -1.0).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,155 @@
+use crate::math::{Real, Vector3};
+use crate::particle::Particle;
+use crate::particle_forces::{Atmosphere, ParticleForceGenerator};
+
+use std::f32::consts::PI;
+
+/// The density of air at sea level (kg/m^3)
+pub const SEA_LEVEL_AIR_DENSITY: Real = 1.225;
+
+/// Generates quadratic air drag and Magnus lift on a spinning projectile,
+/// such as a ball
+pub struct ParticleAerodynamics {
+    /// The dimensionless drag coefficient (e.g. a smooth sphere = 0.47)
+    drag_coefficient: Real,
+
+    /// The area the projectile presents to the air
+    cross_sectional_area: Real,
+
+    /// The radius used to relate spin to the lift it produces
+    radius: Real,
+
+    /// The density of the surrounding air
+    air_density: Real,
+
+    /// If set, the air density is sampled at the particle instead
+    atmosphere: Option<Atmosphere>,
+
+    /// The angular velocity of the projectile, in radians per second
+    spin: Vector3,
+}
+
+impl ParticleForceGenerator for ParticleAerodynamics {
+    fn update_force(&mut self, particle: &mut Particle, _duration: Real) {
+        let velocity = particle.get_velocity();
+        let speed = velocity.magnitude();
+        if speed == 0.0 {
+            return;
+        }
+
+        let air_density = match &self.atmosphere {
+            Some(atmosphere) => atmosphere.density_at(&particle.get_position()),
+            None => self.air_density,
+        };
+
+        // Drag opposes the velocity with force proportional to its square
+        let dynamic_pressure = 0.5 * air_density * speed.powi(2);
+        let reference = dynamic_pressure * self.cross_sectional_area;
+        particle.add_force(&(velocity * (-reference * self.drag_coefficient / speed)));
+
+        // Spin deflects the air to one side, lifting the projectile
+        // towards the side spinning into the flow
+        let lift_direction = self.spin % velocity;
+        let lift_magnitude = lift_direction.magnitude();
+        if lift_magnitude == 0.0 {
+            return;
+        }
+        let lift = reference * self.lift_coefficient(speed);
+        particle.add_force(&(lift_direction * (lift / lift_magnitude)));
+    }
+}
+
+impl ParticleAerodynamics {
+    pub fn new(
+        drag_coefficient: Real,
+        cross_sectional_area: Real,
+        radius: Real,
+        air_density: Real,
+    ) -> Self {
+        Self {
+            drag_coefficient,
+            cross_sectional_area,
+            radius,
+            air_density,
+            atmosphere: None,
+            spin: Vector3::default(),
+        }
+    }
+
+    /// Creates a generator for a ball of the given radius in air at sea level
+    pub fn ball(drag_coefficient: Real, radius: Real) -> Self {
+        Self::new(
+            drag_coefficient,
+            PI * radius.powi(2),
+            radius,
+            SEA_LEVEL_AIR_DENSITY,
+        )
+    }
+
+    /// Returns the lift coefficient at the given speed, which grows with the
+    /// ratio of surface speed to airspeed and levels off for fast spins
+    pub fn lift_coefficient(&self, speed: Real) -> Real {
+        let spin_ratio = self.radius * self.spin.magnitude() / speed;
+        if spin_ratio == 0.0 {
+            0.0
+        } else {
+            1.0 / (2.0 + 1.0 / spin_ratio)
+        }
+    }
+
+    pub fn get_spin(&self) -> Vector3 {
+        self.spin
+    }
+
+    pub fn set_spin(&mut self, spin: Vector3) {
+        self.spin = spin;
+    }
+
+    pub fn get_air_density(&self) -> Real {
+        self.air_density
+    }
+
+    pub fn set_air_density(&mut self, air_density: Real) {
+        self.air_density = air_density;
+    }
+
+    /// Samples the air density from an atmosphere model
+    /// instead of using a fixed density
+    pub fn set_atmosphere(&mut self, atmosphere: Option<Atmosphere>) {
+        self.atmosphere = atmosphere;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn launch(aerodynamics: &mut ParticleAerodynamics) -> Vector3 {
+        let mut particle = Particle::default();
+        particle.set_mass(1.0);
+        particle.set_damping(1.0);
+        particle.set_velocity(2.0, 0.0, 0.0);
+        aerodynamics.update_force(&mut particle, 1.0);
+        particle.integrate(1.0);
+        particle.get_velocity()
+    }
+
+    #[test]
+    fn drag_grows_with_the_square_of_speed() {
+        // 0.5 * 2.0 * 2.0^2 = 4.0 opposing the motion
+        let mut aerodynamics = ParticleAerodynamics::new(1.0, 1.0, 1.0, 2.0);
+        let velocity = launch(&mut aerodynamics);
+        assert!((velocity.x - -2.0).abs() < 1e-5 && velocity.y == 0.0);
+    }
+
+    #[test]
+    fn backspin_lifts() {
+        let mut aerodynamics = ParticleAerodynamics::new(0.0, 1.0, 1.0, 2.0);
+        aerodynamics.set_spin(Vector3::new(0.0, 0.0, 1.0));
+
+        // A spin ratio of 0.5 gives a lift coefficient of 0.25
+        assert!((aerodynamics.lift_coefficient(2.0) - 0.25).abs() < 1e-5);
+        let velocity = launch(&mut aerodynamics);
+        assert!((velocity.y - 1.0).abs() < 1e-5 && (velocity.x - 2.0).abs() < 1e-5);
+    }
+}
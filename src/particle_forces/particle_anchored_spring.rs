@@ -1,5 +1,6 @@
 use crate::math::{Real, Vector3};
 use crate::particle::Particle;
+use crate::particle_forces::spring_damping::{self, SpringDamping};
 use crate::particle_forces::ParticleForceGenerator;
 
 /// Generates a spring force relative to a fixed anchor
@@ -7,6 +8,9 @@ pub struct ParticleAnchoredSpring {
     anchor: Vector3,
     spring_constant: Real,
     rest_length: Real,
+
+    /// The force opposing the particle's velocity along the spring, per unit speed
+    damping: Real,
 }
 
 impl ParticleForceGenerator for ParticleAnchoredSpring {
@@ -21,17 +25,91 @@ impl ParticleForceGenerator for ParticleAnchoredSpring {
 
         // A spring acts to pull the particle towards the rest length
         // with force proportional to the spring constant
-        let magnitude = self.spring_constant * (distance - self.rest_length).abs();
+        let mut magnitude = self.spring_constant * (distance - self.rest_length);
+
+        // The damper resists the particle moving towards or away from the anchor
+        magnitude += self.damping * (particle.get_velocity() * direction);
+
         particle.add_force(&(direction * -magnitude));
     }
+
+    fn potential_energy_of(&self, particle: &Particle) -> Option<Real> {
+        let extension = (particle.get_position() - self.anchor).magnitude() - self.rest_length;
+        Some(0.5 * self.spring_constant * extension.powi(2))
+    }
 }
 
 impl ParticleAnchoredSpring {
     pub fn new(anchor: Vector3, spring_constant: Real, rest_length: Real) -> Self {
+        Self::with_damping(anchor, spring_constant, 0.0, rest_length)
+    }
+
+    pub fn with_damping(
+        anchor: Vector3,
+        spring_constant: Real,
+        damping: Real,
+        rest_length: Real,
+    ) -> Self {
         Self {
             anchor,
             spring_constant,
             rest_length,
+            damping,
         }
     }
+
+    /// Creates a spring whose constants give the particle
+    /// the given natural frequency (in hertz) and damping ratio
+    pub fn from_frequency(
+        particle: &Particle,
+        anchor: Vector3,
+        frequency: Real,
+        damping_ratio: Real,
+        rest_length: Real,
+    ) -> Self {
+        let (spring_constant, damping) =
+            spring_damping::spring_constants(particle.get_mass(), frequency, damping_ratio);
+        Self::with_damping(anchor, spring_constant, damping, rest_length)
+    }
+
+    pub fn get_spring_constant(&self) -> Real {
+        self.spring_constant
+    }
+
+    pub fn get_damping(&self) -> Real {
+        self.damping
+    }
+
+    /// Returns the damping ratio of the spring attached to the given particle
+    pub fn damping_ratio(&self, particle: &Particle) -> Real {
+        spring_damping::damping_ratio(particle.get_mass(), self.spring_constant, self.damping)
+    }
+
+    /// Classifies the spring's motion when integrated over steps of the given duration
+    pub fn damping_at(&self, particle: &Particle, duration: Real) -> SpringDamping {
+        SpringDamping::from_step(
+            particle.get_mass(),
+            self.spring_constant,
+            self.damping,
+            particle.get_damping(),
+            duration,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compressed_springs_push_away_from_the_anchor() {
+        let mut particle = Particle::default();
+        particle.set_mass(1.0);
+        particle.set_damping(1.0);
+        particle.set_position(1.0, 0.0, 0.0);
+
+        ParticleAnchoredSpring::new(Vector3::default(), 10.0, 2.0).update_force(&mut particle, 0.1);
+        particle.integrate(1.0);
+        assert_eq!(particle.get_velocity().x, 10.0);
+    }
 }
@@ -0,0 +1,34 @@
+use crate::math::{Random, Real};
+use crate::particle::Particle;
+use crate::particle_forces::ParticleForceGenerator;
+
+/// Generates a random force giving a particle Brownian motion.
+/// The force is scaled with the step so that the particle's momentum
+/// random walk is the same whatever the step duration. The same seed and
+/// the same order of updates always give the same motion.
+pub struct ParticleBrownian {
+    /// The standard deviation of each component of the impulse
+    /// delivered over one second
+    strength: Real,
+    random: Random,
+}
+
+impl ParticleForceGenerator for ParticleBrownian {
+    fn update_force(&mut self, particle: &mut Particle, duration: Real) {
+        let kick = self.random.gaussian_vector();
+        particle.add_force(&(kick * (self.strength / duration.sqrt())));
+    }
+}
+
+impl ParticleBrownian {
+    pub fn new(strength: Real, seed: u64) -> Self {
+        Self {
+            strength,
+            random: Random::new(seed),
+        }
+    }
+
+    pub fn set_strength(&mut self, strength: Real) {
+        self.strength = strength;
+    }
+}
@@ -1,5 +1,6 @@
 use crate::math::Real;
 use crate::particle::Particle;
+use crate::particle_forces::spring_damping::{self, SpringDamping};
 use crate::particle_forces::ParticleForceGenerator;
 
 use std::cell::RefCell;
@@ -11,35 +12,131 @@ pub struct ParticleBungee {
     other: Rc<RefCell<Particle>>,
     spring_constant: Real,
     rest_length: Real,
+
+    /// The force opposing the relative velocity along the bungee
+    /// while it is stretched, per unit speed
+    damping: Real,
+
+    /// Whether a matching bungee is registered on the other particle,
+    /// so each end reports half the bungee's energy
+    two_sided: bool,
 }
 
 impl ParticleForceGenerator for ParticleBungee {
     fn update_force(&mut self, particle: &mut Particle, _duration: Real) {
-        let difference = particle.get_position() - self.other.borrow_mut().get_position();
-        let direction = {
-            let mut temp = difference;
-            temp.normalize();
-            temp
-        };
+        let other = self.other.borrow();
+        let difference = particle.get_position() - other.get_position();
         let distance = difference.magnitude();
 
         if distance <= self.rest_length {
             return;
         }
 
+        let direction = {
+            let mut temp = difference;
+            temp.normalize();
+            temp
+        };
+
         // A spring acts to pull the particle towards the rest length
         // with force proportional to the spring constant
-        let magnitude = self.spring_constant * (distance - self.rest_length);
+        let mut magnitude = self.spring_constant * (distance - self.rest_length);
+
+        // The damper resists the particles moving apart or together
+        let relative_velocity = particle.get_velocity() - other.get_velocity();
+        magnitude += self.damping * (relative_velocity * direction);
+
         particle.add_force(&(direction * -magnitude));
     }
+
+    /// The bungee's energy, halved if it is two-sided
+    fn potential_energy_of(&self, particle: &Particle) -> Option<Real> {
+        let extension = (particle.get_position() - self.other.borrow().get_position()).magnitude()
+            - self.rest_length;
+        Some(self.energy_share() * self.spring_constant * extension.max(0.0).powi(2))
+    }
 }
 
 impl ParticleBungee {
+    /// Creates a bungee pulling the particle it is registered on towards
+    /// the other. It is two-sided, expecting a matching bungee registered
+    /// on the other particle, unless `set_two_sided` says otherwise.
     pub fn new(other: Rc<RefCell<Particle>>, spring_constant: Real, rest_length: Real) -> Self {
+        Self::with_damping(other, spring_constant, 0.0, rest_length)
+    }
+
+    /// Creates a damped bungee, which is two-sided as `new` says
+    pub fn with_damping(
+        other: Rc<RefCell<Particle>>,
+        spring_constant: Real,
+        damping: Real,
+        rest_length: Real,
+    ) -> Self {
         Self {
             other,
             spring_constant,
             rest_length,
+            damping,
+            two_sided: true,
         }
     }
+
+    /// Creates a bungee whose constants give the two particles the given
+    /// natural frequency (in hertz) and damping ratio while it is stretched,
+    /// when the bungee is registered on both of them
+    pub fn from_frequency(
+        particle: &Particle,
+        other: Rc<RefCell<Particle>>,
+        frequency: Real,
+        damping_ratio: Real,
+        rest_length: Real,
+    ) -> Self {
+        let mass = spring_damping::reduced_mass(particle, &other.borrow());
+        let (spring_constant, damping) =
+            spring_damping::spring_constants(mass, frequency, damping_ratio);
+        Self::with_damping(other, spring_constant, damping, rest_length)
+    }
+
+    pub fn get_spring_constant(&self) -> Real {
+        self.spring_constant
+    }
+
+    pub fn get_damping(&self) -> Real {
+        self.damping
+    }
+
+    /// Sets whether a matching bungee is registered on the other particle.
+    /// A one-sided bungee, registered on a single end, reports all of
+    /// its energy rather than half.
+    pub fn set_two_sided(&mut self, two_sided: bool) {
+        self.two_sided = two_sided;
+    }
+
+    /// The part of 1/2 k x^2 reported by this end
+    fn energy_share(&self) -> Real {
+        if self.two_sided {
+            0.25
+        } else {
+            0.5
+        }
+    }
+
+    /// Returns the damping ratio of the bungee between the given particle and the other
+    pub fn damping_ratio(&self, particle: &Particle) -> Real {
+        let mass = spring_damping::reduced_mass(particle, &self.other.borrow());
+        spring_damping::damping_ratio(mass, self.spring_constant, self.damping)
+    }
+
+    /// Classifies the bungee's stretched motion when integrated
+    /// over steps of the given duration
+    pub fn damping_at(&self, particle: &Particle, duration: Real) -> SpringDamping {
+        let mass = spring_damping::reduced_mass(particle, &self.other.borrow());
+        SpringDamping::from_step(
+            mass,
+            self.spring_constant,
+            self.damping,
+            particle.get_damping(),
+            duration,
+        )
+    }
 }
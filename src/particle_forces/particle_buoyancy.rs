@@ -1,56 +1,204 @@
-use crate::math::{Real, Vector3};
+use crate::math::Real;
 use crate::particle::Particle;
-use crate::particle_forces::ParticleForceGenerator;
+use crate::particle_forces::{LiquidPlane, LiquidSurface, ParticleForceGenerator};
 
-/// Simulate a simple buoyancy force relative to a plane of liquid
+use std::rc::Rc;
+
+/// Simulate a simple buoyancy force relative to the surface of a liquid
 pub struct ParticleBuoyancy {
-    /// The depth after which the generator produces its maximal force
+    /// The depth after which the generator produces its maximal force.
+    /// The object is treated as extending this far either side of the particle.
     max_depth: Real,
 
     /// The volume of the object being submerged
     volume: Real,
 
-    /// The height of the plane of liquid above y=0
-    liquid_height: Real,
+    /// The surface of the liquid, which may move over time
+    surface: Rc<dyn LiquidSurface>,
 
     /// The density of the liquid (e.g. water = 1000 kg/m^3)
     liquid_density: Real,
+
+    /// The magnitude of the gravitational acceleration displacing the liquid.
+    /// Defaults to 1.0, so the maximal force is the mass of liquid displaced.
+    gravity: Real,
+
+    /// Drag coefficients applied in proportion to how much of the object
+    /// is submerged, depending directly and squarely on its velocity
+    k1: Real,
+    k2: Real,
+
+    /// The simulation time used to sample a moving surface
+    time: Real,
 }
 
 impl ParticleForceGenerator for ParticleBuoyancy {
     fn update_force(&mut self, particle: &mut Particle, _duration: Real) {
-        let depth = particle.get_position().y;
+        let position = particle.get_position();
+        let submerged = self.submerged_at_depth(self.surface.depth(&position, self.time));
 
         // Return if the particle is out of the water
-        if depth >= self.liquid_height + self.max_depth {
+        if submerged == 0.0 {
             return;
         }
 
-        let force = if depth <= self.liquid_height - self.max_depth {
-            // If at or below maximum depth, apply maximum force
-            self.liquid_density * self.volume
-        } else {
-            // Otherwise, the force depends on depth
-            self.liquid_density
-                * self.volume
-                * ((depth - self.max_depth - self.liquid_height) / 2.0)
-                * self.max_depth
-        };
-        particle.add_force(&Vector3::new(0.0, force, 0.0));
+        let force = self.liquid_density * self.volume * self.gravity * submerged;
+        particle.add_force(&(self.surface.normal(&position, self.time) * force));
+
+        // Apply drag in proportion to the submerged fraction
+        let velocity = particle.get_velocity();
+        let speed = velocity.magnitude();
+        if speed > 0.0 {
+            let drag_coeff = (self.k1 + self.k2 * speed) * submerged;
+            particle.add_force(&(velocity * -drag_coeff));
+        }
+    }
+
+    fn advance(&mut self, duration: Real) {
+        self.time += duration;
     }
 }
 
 impl ParticleBuoyancy {
     pub fn new(max_depth: Real, volume: Real, liquid_height: Real, liquid_density: Real) -> Self {
-        Self {
+        Self::with_surface(
             max_depth,
             volume,
-            liquid_height,
+            Rc::new(LiquidPlane::horizontal(liquid_height)),
             liquid_density,
-        }
+        )
     }
 
     pub fn new_water(max_depth: Real, volume: Real, liquid_height: Real) -> Self {
         Self::new(max_depth, volume, liquid_height, 1000.0)
     }
+
+    /// Creates a buoyancy force relative to any liquid surface,
+    /// which may be shared between many generators
+    pub fn with_surface(
+        max_depth: Real,
+        volume: Real,
+        surface: Rc<dyn LiquidSurface>,
+        liquid_density: Real,
+    ) -> Self {
+        Self::with_gravity(max_depth, volume, surface, liquid_density, 1.0)
+    }
+
+    /// Creates a buoyancy force relative to any liquid surface, scaled by
+    /// gravity (e.g. 9.81) so that the maximal force is the weight of
+    /// liquid displaced
+    pub fn with_gravity(
+        max_depth: Real,
+        volume: Real,
+        surface: Rc<dyn LiquidSurface>,
+        liquid_density: Real,
+        gravity: Real,
+    ) -> Self {
+        // A max depth of zero makes a step, with the full force as soon
+        // as the particle is below the surface
+        assert!(
+            max_depth >= 0.0,
+            "attempted to create a buoyancy force with a negative max depth",
+        );
+
+        Self {
+            max_depth,
+            volume,
+            surface,
+            liquid_density,
+            gravity,
+            k1: 0.0,
+            k2: 0.0,
+            time: 0.0,
+        }
+    }
+
+    /// Sets the magnitude of gravity, which scales the buoyancy force
+    pub fn set_gravity(&mut self, gravity: Real) {
+        self.gravity = gravity;
+    }
+
+    /// Sets the drag coefficients applied while fully submerged
+    pub fn set_drag(&mut self, k1: Real, k2: Real) {
+        self.k1 = k1;
+        self.k2 = k2;
+    }
+
+    /// Returns how far the particle is submerged, from 0.0 (clear of the liquid)
+    /// to 1.0 (at or below max depth)
+    pub fn submerged_fraction(&self, particle: &Particle) -> Real {
+        self.submerged_at_depth(self.surface.depth(&particle.get_position(), self.time))
+    }
+
+    pub fn get_time(&self) -> Real {
+        self.time
+    }
+
+    pub fn set_time(&mut self, time: Real) {
+        self.time = time;
+    }
+
+    /// The submerged fraction ramps linearly from nothing, when the object's top
+    /// touches the surface, to everything, when its bottom reaches max depth
+    fn submerged_at_depth(&self, depth: Real) -> Real {
+        if self.max_depth == 0.0 {
+            if depth > 0.0 {
+                1.0
+            } else {
+                0.0
+            }
+        } else {
+            ((depth + self.max_depth) / (2.0 * self.max_depth)).clamp(0.0, 1.0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Vector3;
+
+    /// Returns the velocity a unit mass at the given height gains
+    /// over a unit step, which equals the force on it
+    fn push(buoyancy: &mut ParticleBuoyancy, height: Real) -> Vector3 {
+        let mut particle = Particle::default();
+        particle.set_mass(1.0);
+        particle.set_damping(1.0);
+        particle.set_position(0.0, height, 0.0);
+        buoyancy.update_force(&mut particle, 1.0);
+        particle.integrate(1.0);
+        particle.get_velocity()
+    }
+
+    #[test]
+    fn partial_submersion_ramps_up_the_force() {
+        let mut buoyancy = ParticleBuoyancy::new_water(0.5, 0.1, 1.0);
+        let full = 1000.0 * 0.1;
+        for &(height, fraction) in &[(2.0, 0.0), (1.5, 0.0), (1.25, 0.25), (1.0, 0.5), (0.5, 1.0)] {
+            assert!((push(&mut buoyancy, height).y - full * fraction).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn pushes_along_the_surface_normal() {
+        let wall = LiquidPlane::new(Vector3::new(1.0, 0.0, 0.0), Vector3::default());
+        let mut buoyancy = ParticleBuoyancy::with_surface(0.5, 0.1, Rc::new(wall), 1000.0);
+        let push = push(&mut buoyancy, 0.0);
+        assert!((push.x - 50.0).abs() < 1e-3 && push.y == 0.0);
+    }
+
+    #[test]
+    fn gravity_scales_to_the_weight_displaced() {
+        let surface = Rc::new(LiquidPlane::horizontal(1.0));
+        let mut buoyancy = ParticleBuoyancy::with_gravity(0.5, 0.1, surface, 1000.0, 9.81);
+        assert!((push(&mut buoyancy, 0.0).y - 981.0).abs() < 1e-2);
+    }
+
+    #[test]
+    fn zero_max_depth_is_a_step() {
+        let mut buoyancy = ParticleBuoyancy::new_water(0.0, 0.1, 1.0);
+        assert_eq!(push(&mut buoyancy, 1.0).y, 0.0);
+        assert!((push(&mut buoyancy, 0.999).y - 100.0).abs() < 1e-3);
+        assert!((push(&mut buoyancy, -5.0).y - 100.0).abs() < 1e-3);
+    }
 }
@@ -0,0 +1,127 @@
+use crate::math::Real;
+use crate::particle::Particle;
+use crate::particle_forces::ParticleForceGenerator;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Coulomb's constant in SI units (N m^2 / C^2)
+pub const COULOMB_CONSTANT: Real = 8.987_552e9;
+
+/// Generates the electrostatic force on a charged particle from a set of
+/// other charged particles. A single generator can hold every charge in a
+/// system and be registered on each of them, since a particle never
+/// acts on itself.
+pub struct ParticleCoulomb {
+    sources: Vec<Rc<RefCell<Particle>>>,
+    coulomb_constant: Real,
+
+    /// A small distance added in quadrature to the separation,
+    /// keeping the force finite when charges pass very close
+    softening: Real,
+}
+
+impl ParticleForceGenerator for ParticleCoulomb {
+    fn update_force(&mut self, particle: &mut Particle, _duration: Real) {
+        let charge = particle.get_charge();
+        if charge == 0.0 {
+            return;
+        }
+
+        for source in &self.sources {
+            // Skip the particle being updated, which is already borrowed
+            if std::ptr::eq(source.as_ptr(), particle) {
+                continue;
+            }
+
+            let source = source.borrow();
+            let difference = particle.get_position() - source.get_position();
+            let square_distance = difference.square_magnitude() + self.softening.powi(2);
+            if square_distance == 0.0 {
+                continue;
+            }
+
+            // Like charges repel and unlike charges attract,
+            // with an inverse square dependence on distance
+            let magnitude = self.coulomb_constant * charge * source.get_charge() / square_distance;
+            particle.add_force(&(difference * (magnitude / square_distance.sqrt())));
+        }
+    }
+}
+
+impl ParticleCoulomb {
+    pub fn new(sources: Vec<Rc<RefCell<Particle>>>) -> Self {
+        Self::with_constant(sources, COULOMB_CONSTANT)
+    }
+
+    /// Creates a generator with a chosen Coulomb constant,
+    /// e.g. 1.0 for simulations in scaled units
+    pub fn with_constant(sources: Vec<Rc<RefCell<Particle>>>, coulomb_constant: Real) -> Self {
+        Self {
+            sources,
+            coulomb_constant,
+            softening: 0.0,
+        }
+    }
+
+    pub fn add_source(&mut self, source: Rc<RefCell<Particle>>) {
+        self.sources.push(source);
+    }
+
+    pub fn set_softening(&mut self, softening: Real) {
+        self.softening = softening;
+    }
+
+    /// Returns the electrostatic potential energy between the given particle and the sources
+    pub fn potential_energy(&self, particle: &Particle) -> Real {
+        self.sources
+            .iter()
+            .filter(|source| !std::ptr::eq(source.as_ptr(), particle))
+            .map(|source| {
+                let source = source.borrow();
+                let distance = ((particle.get_position() - source.get_position())
+                    .square_magnitude()
+                    + self.softening.powi(2))
+                .sqrt();
+                self.coulomb_constant * particle.get_charge() * source.get_charge() / distance
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn charge(x: Real, charge: Real) -> Rc<RefCell<Particle>> {
+        let mut particle = Particle::default();
+        particle.set_mass(1.0);
+        particle.set_damping(1.0);
+        particle.set_position(x, 0.0, 0.0);
+        particle.set_charge(charge);
+        Rc::new(RefCell::new(particle))
+    }
+
+    #[test]
+    fn follows_the_inverse_square_law() {
+        let near = charge(0.0, 2.0);
+        let far = charge(2.0, 3.0);
+        let mut coulomb = ParticleCoulomb::with_constant(vec![near.clone(), far.clone()], 1.0);
+
+        // The generator holds the updated particle too, which must be skipped
+        coulomb.update_force(&mut near.borrow_mut(), 1.0);
+        near.borrow_mut().integrate(1.0);
+        assert!((near.borrow().get_velocity().x - -1.5).abs() < 1e-5);
+        assert!((coulomb.potential_energy(&near.borrow()) - 3.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn unlike_charges_attract() {
+        let positive = charge(0.0, 1.0);
+        let negative = charge(1.0, -1.0);
+        let mut coulomb = ParticleCoulomb::with_constant(vec![negative], 1.0);
+        coulomb.update_force(&mut positive.borrow_mut(), 1.0);
+        positive.borrow_mut().integrate(1.0);
+        assert!((positive.borrow().get_velocity().x - 1.0).abs() < 1e-5);
+    }
+}
@@ -1,30 +1,50 @@
 use crate::math::Real;
 use crate::particle::Particle;
-use crate::particle_forces::ParticleForceGenerator;
+use crate::particle_forces::{Atmosphere, ParticleForceGenerator};
 
 /// Generates drag on a particle depending directly and squarely on its velocity
 pub struct ParticleDrag {
     k1: Real,
     k2: Real,
+
+    /// If set, the coefficients hold at sea level and
+    /// are scaled with the air density at the particle
+    atmosphere: Option<Atmosphere>,
 }
 
 impl ParticleForceGenerator for ParticleDrag {
     fn update_force(&mut self, particle: &mut Particle, _duration: Real) {
         let velocity = particle.get_velocity();
-        let direction = {
-            let mut temp = velocity;
-            temp.normalize();
-            temp
-        };
         let speed = velocity.magnitude();
+        if speed == 0.0 {
+            return;
+        }
+        let direction = velocity * (1.0 / speed);
 
-        let drag_coeff = self.k1 * speed + self.k2 * speed.powi(2);
+        let mut drag_coeff = self.k1 * speed + self.k2 * speed.powi(2);
+        if let Some(atmosphere) = &self.atmosphere {
+            drag_coeff *= atmosphere.relative_density_at(&particle.get_position());
+        }
         particle.add_force(&(direction * -drag_coeff));
     }
 }
 
 impl ParticleDrag {
     pub fn new(k1: Real, k2: Real) -> Self {
-        Self { k1, k2 }
+        Self {
+            k1,
+            k2,
+            atmosphere: None,
+        }
+    }
+
+    /// Creates drag which thins out with altitude,
+    /// with the given coefficients holding at sea level
+    pub fn with_atmosphere(k1: Real, k2: Real, atmosphere: Atmosphere) -> Self {
+        Self {
+            k1,
+            k2,
+            atmosphere: Some(atmosphere),
+        }
     }
 }
@@ -1,11 +1,16 @@
 use crate::math::{Real, Vector3};
 use crate::particle::Particle;
+use crate::particle_forces::spring_damping::{self, SpringDamping};
 use crate::particle_forces::ParticleForceGenerator;
 
 /// Generates a force emulating to a stiff spring
 pub struct ParticleFakeSpring {
     anchor: Vector3,
+
+    /// The spring constant per unit mass, i.e. the square of the angular frequency
     spring_constant: Real,
+
+    /// The damping coefficient per unit mass
     damping: Real,
 }
 
@@ -16,19 +21,32 @@ impl ParticleForceGenerator for ParticleFakeSpring {
         }
 
         let difference = particle.get_position() - self.anchor;
+        let velocity = particle.get_velocity();
 
-        // Calculate and bounds-check constants
-        let gamma = 0.5 * (4.0 * self.spring_constant * self.damping.powi(2)).sqrt();
-        if gamma == 0.0 {
-            return;
-        }
-        let c =
-            difference * (self.damping / (2.0 * gamma)) + particle.get_velocity() * (1.0 / gamma);
+        // Find where the particle would be after the step by solving
+        // the damped harmonic oscillator exactly
+        let decay = -0.5 * self.damping;
+        let target = match self.damping_regime() {
+            SpringDamping::Underdamped => {
+                let gamma = 0.5 * (4.0 * self.spring_constant - self.damping.powi(2)).sqrt();
+                let c = difference * (self.damping / (2.0 * gamma)) + velocity * (1.0 / gamma);
+                (difference * (gamma * duration).cos() + c * (gamma * duration).sin())
+                    * (decay * duration).exp()
+            }
+            SpringDamping::CriticallyDamped => {
+                let c = velocity + difference * (-decay);
+                (difference + c * duration) * (decay * duration).exp()
+            }
+            SpringDamping::Overdamped => {
+                let spread = 0.5 * (self.damping.powi(2) - 4.0 * self.spring_constant).sqrt();
+                let (slow, fast) = (decay + spread, decay - spread);
+                let a = (velocity - difference * fast) * (1.0 / (slow - fast));
+                let b = difference - a;
+                a * (slow * duration).exp() + b * (fast * duration).exp()
+            }
+        };
 
-        let target = (difference * (gamma * duration).cos() + c * (gamma * duration).sin())
-            * (-0.5 * duration * self.damping).exp();
-        let accel =
-            (target - difference) * (1.0 / duration.powi(2)) - particle.get_velocity() * duration;
+        let accel = (target - difference) * (1.0 / duration.powi(2)) - velocity * (1.0 / duration);
         particle.add_force(&(accel * particle.get_mass()));
     }
 }
@@ -41,4 +59,61 @@ impl ParticleFakeSpring {
             damping,
         }
     }
+
+    /// Creates a fake spring with the given natural frequency (in hertz)
+    /// and damping ratio. The constants are per unit mass, so the
+    /// generator behaves the same for any particle it is registered on.
+    pub fn from_frequency(anchor: Vector3, frequency: Real, damping_ratio: Real) -> Self {
+        let (spring_constant, damping) =
+            spring_damping::spring_constants(1.0, frequency, damping_ratio);
+        Self::new(anchor, spring_constant, damping)
+    }
+
+    pub fn get_spring_constant(&self) -> Real {
+        self.spring_constant
+    }
+
+    pub fn get_damping(&self) -> Real {
+        self.damping
+    }
+
+    pub fn damping_ratio(&self) -> Real {
+        spring_damping::damping_ratio(1.0, self.spring_constant, self.damping)
+    }
+
+    /// Classifies the spring's motion. Since the fake spring is solved
+    /// exactly, this holds for steps of any duration.
+    pub fn damping_regime(&self) -> SpringDamping {
+        let discriminant = 4.0 * self.spring_constant - self.damping.powi(2);
+        let scale = 4.0 * self.spring_constant + self.damping.powi(2);
+        if discriminant.abs() <= spring_damping::CRITICAL_TOLERANCE * scale {
+            SpringDamping::CriticallyDamped
+        } else if discriminant > 0.0 {
+            SpringDamping::Underdamped
+        } else {
+            SpringDamping::Overdamped
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moves_towards_the_exact_position() {
+        let mut particle = Particle::default();
+        particle.set_mass(3.0);
+        particle.set_damping(1.0);
+        particle.set_position(1.0, 0.0, 0.0);
+
+        // With an angular frequency of 2 and no damping, the particle
+        // should be at cos(2t) after each step
+        let mut spring = ParticleFakeSpring::new(Vector3::default(), 4.0, 0.0);
+        let duration = 0.1;
+        spring.update_force(&mut particle, duration);
+        particle.integrate(duration);
+        let expected_velocity = ((2.0 * duration).cos() - 1.0) / duration;
+        assert!((particle.get_velocity().x - expected_velocity).abs() < 1e-4);
+    }
 }
@@ -0,0 +1,423 @@
+use crate::math::{Matrix3, Real, Vector3};
+use crate::particle::Particle;
+use crate::particle_forces::particle_group::{ParticleGroup, StepForces};
+use crate::particle_forces::ParticleForceGenerator;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// The most iterations spent extracting the rotation of each element per step
+const ROTATION_ITERATIONS: usize = 10;
+
+/// The rotation, in radians, below which extraction has converged
+const ROTATION_TOLERANCE: Real = 1e-6;
+
+/// The elastic properties of a solid material
+#[derive(Clone, Copy)]
+pub struct FemMaterial {
+    /// The stiffness against stretching (e.g. rubber = 1e6 Pa, wood = 1e10 Pa)
+    pub youngs_modulus: Real,
+
+    /// The ratio of sideways contraction to stretching,
+    /// between -1.0 and 0.5 (e.g. rubber = 0.49, cork = 0.0)
+    pub poisson_ratio: Real,
+
+    /// The viscosity resisting the rate of deformation, in Pa s
+    pub damping: Real,
+}
+
+impl FemMaterial {
+    pub fn new(youngs_modulus: Real, poisson_ratio: Real, damping: Real) -> Self {
+        assert!(
+            youngs_modulus > 0.0,
+            "attempted to create a material with a non-positive Young's modulus",
+        );
+        assert!(
+            poisson_ratio > -1.0 && poisson_ratio < 0.5,
+            "attempted to create a material with a Poisson ratio outside (-1, 0.5)",
+        );
+
+        Self {
+            youngs_modulus,
+            poisson_ratio,
+            damping,
+        }
+    }
+
+    /// Returns the Lamé parameters (shear modulus, first parameter)
+    /// equivalent to the Young's modulus and Poisson ratio
+    pub fn lame_parameters(&self) -> (Real, Real) {
+        let e = self.youngs_modulus;
+        let nu = self.poisson_ratio;
+        let shear = e / (2.0 * (1.0 + nu));
+        let lambda = e * nu / ((1.0 + nu) * (1.0 - 2.0 * nu));
+        (shear, lambda)
+    }
+}
+
+/// How an element's deformation is measured
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FemSolver {
+    /// Linear strain, which is fast but treats rotation as stretching,
+    /// so it is only accurate for small deformations
+    Linear,
+
+    /// Linear strain measured after removing each element's rotation,
+    /// which stays accurate as the body tumbles or bends far
+    Corotational,
+}
+
+/// A tetrahedral element and its rest shape
+struct Element {
+    nodes: [usize; 4],
+
+    /// The inverse of the matrix whose columns are
+    /// the edges from the first node at rest
+    rest_inverse: Matrix3,
+
+    rest_volume: Real,
+
+    /// The rotation found at the latest step, which is
+    /// the starting guess for the next
+    rotation: Matrix3,
+}
+
+/// Generates the elastic forces of a solid made from tetrahedral elements
+/// whose corners are particles, using the finite element method.
+/// The rest shape is taken from the particles' positions on creation.
+/// Stiff materials and small elements need short steps to stay stable.
+/// The forces on every particle are found together at the first update of
+/// each step. A single generator holds the whole solid and is registered
+/// on every particle in it.
+pub struct ParticleFem {
+    group: ParticleGroup,
+
+    elements: Vec<Element>,
+    material: FemMaterial,
+    solver: FemSolver,
+
+    forces: StepForces,
+}
+
+impl ParticleForceGenerator for ParticleFem {
+    fn update_force(&mut self, particle: &mut Particle, _duration: Real) {
+        let index = match self.group.index_of(particle) {
+            Some(index) => index,
+            None => return,
+        };
+
+        if !self.forces.is_found() {
+            let forces = self.compute_forces(particle);
+            self.forces.set(forces);
+        }
+        self.forces.apply(index, particle);
+    }
+
+    fn advance(&mut self, _duration: Real) {
+        self.forces.clear();
+    }
+
+    /// A quarter of the elastic energy of each element the particle is a
+    /// corner of, found from the current positions
+    fn potential_energy_of(&self, particle: &Particle) -> Option<Real> {
+        let index = self.group.index_of(particle)?;
+        let position = |node: usize| {
+            if node == index {
+                particle.get_position()
+            } else {
+                self.group.members()[node].borrow().get_position()
+            }
+        };
+
+        Some(
+            self.elements
+                .iter()
+                .filter(|element| element.nodes.contains(&index))
+                .map(|element| 0.25 * self.element_energy(element, position))
+                .sum(),
+        )
+    }
+}
+
+impl ParticleFem {
+    /// Creates a solid from elements given as four particle indices each,
+    /// panicking if any element is flat
+    pub fn new(
+        particles: Vec<Rc<RefCell<Particle>>>,
+        tetrahedra: &[[usize; 4]],
+        material: FemMaterial,
+        solver: FemSolver,
+    ) -> Self {
+        let elements = tetrahedra
+            .iter()
+            .map(|&nodes| {
+                let corners = |nodes: &[usize; 4]| {
+                    let mut corners = [Vector3::default(); 4];
+                    for (corner, &node) in corners.iter_mut().zip(nodes.iter()) {
+                        *corner = particles[node].borrow().get_position();
+                    }
+                    corners
+                };
+
+                // Order the nodes so the element has a positive volume
+                let mut nodes = nodes;
+                if edge_matrix(&corners(&nodes)).determinant() < 0.0 {
+                    nodes.swap(2, 3);
+                }
+                let edges = edge_matrix(&corners(&nodes));
+                let rest_volume = edges.determinant() / 6.0;
+                let rest_inverse = match edges.inverse() {
+                    Some(inverse) if rest_volume > 0.0 => inverse,
+                    _ => panic!("attempted to create a finite element with no volume"),
+                };
+
+                Element {
+                    nodes,
+                    rest_inverse,
+                    rest_volume,
+                    rotation: Matrix3::identity(),
+                }
+            })
+            .collect();
+
+        Self {
+            group: ParticleGroup::new(particles),
+            elements,
+            material,
+            solver,
+            forces: StepForces::default(),
+        }
+    }
+
+    pub fn get_particles(&self) -> &[Rc<RefCell<Particle>>] {
+        self.group.members()
+    }
+
+    pub fn get_material(&self) -> FemMaterial {
+        self.material
+    }
+
+    pub fn set_material(&mut self, material: FemMaterial) {
+        self.material = material;
+    }
+
+    pub fn get_solver(&self) -> FemSolver {
+        self.solver
+    }
+
+    pub fn set_solver(&mut self, solver: FemSolver) {
+        self.solver = solver;
+    }
+
+    /// Returns the total volume of the elements at rest
+    pub fn rest_volume(&self) -> Real {
+        self.elements
+            .iter()
+            .map(|element| element.rest_volume)
+            .sum()
+    }
+
+    /// Returns the elastic energy stored in the solid at its current shape.
+    /// This borrows every particle, so can't be used while one is being updated.
+    pub fn elastic_energy(&self) -> Real {
+        let position = |node: usize| self.group.members()[node].borrow().get_position();
+        self.elements
+            .iter()
+            .map(|element| self.element_energy(element, position))
+            .sum()
+    }
+
+    /// Finds the energy stored in an element with its corners at the given
+    /// positions, measuring its rotation from the latest step's
+    fn element_energy(&self, element: &Element, position: impl Fn(usize) -> Vector3) -> Real {
+        let [a, b, c, d] = element.nodes;
+        let deformation = edge_matrix(&[position(a), position(b), position(c), position(d)])
+            * element.rest_inverse;
+        let rotation = match self.solver {
+            FemSolver::Linear => Matrix3::identity(),
+            FemSolver::Corotational => extract_rotation(&deformation, element.rotation),
+        };
+        let strain = (rotation.transpose() * deformation).symmetric() - Matrix3::identity();
+
+        let (shear, lambda) = self.material.lame_parameters();
+        let strain_norm: Real = strain.data.iter().map(|value| value * value).sum();
+        element.rest_volume * (shear * strain_norm + 0.5 * lambda * strain.trace().powi(2))
+    }
+
+    fn compute_forces(&mut self, particle: &Particle) -> Vec<Vector3> {
+        let (positions, velocities): (Vec<_>, Vec<_>) = self
+            .group
+            .read(particle, |member| {
+                (member.get_position(), member.get_velocity())
+            })
+            .into_iter()
+            .unzip();
+
+        let (shear, lambda) = self.material.lame_parameters();
+        let identity = Matrix3::identity();
+        let mut forces = vec![Vector3::default(); positions.len()];
+
+        for element in &mut self.elements {
+            let [a, b, c, d] = element.nodes;
+            let deformation =
+                edge_matrix(&[positions[a], positions[b], positions[c], positions[d]])
+                    * element.rest_inverse;
+            let deformation_rate =
+                edge_matrix(&[velocities[a], velocities[b], velocities[c], velocities[d]])
+                    * element.rest_inverse;
+
+            let rotation = match self.solver {
+                FemSolver::Linear => identity,
+                FemSolver::Corotational => {
+                    element.rotation = extract_rotation(&deformation, element.rotation);
+                    element.rotation
+                }
+            };
+            let unrotate = rotation.transpose();
+
+            // Hooke's law on the unrotated strain, plus viscous damping
+            let strain = (unrotate * deformation).symmetric() - identity;
+            let strain_rate = (unrotate * deformation_rate).symmetric();
+            let stress = strain * (2.0 * shear)
+                + identity * (lambda * strain.trace())
+                + strain_rate * self.material.damping;
+
+            // Each column gives the force on one of the last three nodes,
+            // and the first node balances them
+            let nodal = rotation * stress * element.rest_inverse.transpose() * -element.rest_volume;
+            let mut balance = Vector3::default();
+            for (column, &node) in [b, c, d].iter().enumerate() {
+                let force = nodal.column(column);
+                forces[node] += force;
+                balance -= force;
+            }
+            forces[a] += balance;
+        }
+        forces
+    }
+}
+
+/// Returns the matrix whose columns are the edges from the first corner
+fn edge_matrix(corners: &[Vector3; 4]) -> Matrix3 {
+    Matrix3::from_columns(
+        corners[1] - corners[0],
+        corners[2] - corners[0],
+        corners[3] - corners[0],
+    )
+}
+
+/// Finds the rotation closest to a deformation, starting from a guess,
+/// by repeatedly turning the guess's axes towards the deformation's columns.
+/// This stays stable even when the element is flattened or inverted.
+fn extract_rotation(deformation: &Matrix3, guess: Matrix3) -> Matrix3 {
+    let mut rotation = guess;
+    for _ in 0..ROTATION_ITERATIONS {
+        let mut torque = Vector3::default();
+        let mut alignment = 0.0;
+        for column in 0..3 {
+            torque += rotation.column(column) % deformation.column(column);
+            alignment += rotation.column(column) * deformation.column(column);
+        }
+
+        let axis = torque * (1.0 / (alignment.abs() + 1e-9));
+        let angle = axis.magnitude();
+        if angle < ROTATION_TOLERANCE {
+            break;
+        }
+        rotation = Matrix3::rotation(axis * (1.0 / angle), angle) * rotation;
+    }
+    orthonormalized(&rotation)
+}
+
+/// Removes the rounding error built up in a rotation matrix
+fn orthonormalized(rotation: &Matrix3) -> Matrix3 {
+    let mut x = rotation.column(0);
+    x.normalize();
+    let mut z = x % rotation.column(1);
+    z.normalize();
+    let y = z % x;
+    Matrix3::from_columns(x, y, z)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the corners of a unit right tetrahedron, each transformed by a matrix
+    fn corners(transform: Matrix3) -> Vec<Rc<RefCell<Particle>>> {
+        [
+            (0.0, 0.0, 0.0),
+            (1.0, 0.0, 0.0),
+            (0.0, 1.0, 0.0),
+            (0.0, 0.0, 1.0),
+        ]
+        .iter()
+        .map(|&(x, y, z)| {
+            let position = transform * Vector3::new(x, y, z);
+            let mut particle = Particle::default();
+            particle.set_mass(1.0);
+            particle.set_position(position.x, position.y, position.z);
+            Rc::new(RefCell::new(particle))
+        })
+        .collect()
+    }
+
+    /// Moves the corners to the given transform of their rest shape
+    /// and returns the largest force on any of them
+    fn largest_force(solver: FemSolver, transform: Matrix3) -> Real {
+        let particles = corners(Matrix3::identity());
+        let material = FemMaterial::new(1000.0, 0.3, 0.0);
+        let mut fem = ParticleFem::new(particles.clone(), &[[0, 1, 2, 3]], material, solver);
+        for (particle, moved) in particles.iter().zip(corners(transform)) {
+            let position = moved.borrow().get_position();
+            particle
+                .borrow_mut()
+                .set_position(position.x, position.y, position.z);
+        }
+
+        particles
+            .iter()
+            .map(|particle| {
+                fem.update_force(&mut particle.borrow_mut(), 0.01);
+                particle.borrow().get_accumulated_force().magnitude()
+            })
+            .fold(0.0, Real::max)
+    }
+
+    #[test]
+    fn stretching_stores_energy_and_pulls_back() {
+        let particles = corners(Matrix3::identity());
+        let material = FemMaterial::new(1000.0, 0.3, 0.0);
+        let mut fem = ParticleFem::new(
+            particles.clone(),
+            &[[0, 1, 3, 2]],
+            material,
+            FemSolver::Linear,
+        );
+        assert!((fem.rest_volume() - 1.0 / 6.0).abs() < 1e-6);
+
+        assert_eq!(fem.elastic_energy(), 0.0);
+
+        // The energy follows the current positions, without an update
+        particles[1].borrow_mut().set_position(1.2, 0.0, 0.0);
+        let energy = fem.elastic_energy();
+        assert!(energy > 0.0);
+        let shares: Real = particles
+            .iter()
+            .filter_map(|particle| fem.potential_energy_of(&particle.borrow()))
+            .sum();
+        assert!((shares - energy).abs() < 1e-4);
+
+        fem.update_force(&mut particles[1].borrow_mut(), 0.01);
+        assert!(particles[1].borrow().get_accumulated_force().x < 0.0);
+    }
+
+    #[test]
+    fn corotation_ignores_rigid_rotation() {
+        let mut axis = Vector3::new(1.0, 1.0, 0.0);
+        axis.normalize();
+        let rotation = Matrix3::rotation(axis, 1.0);
+        assert!(largest_force(FemSolver::Corotational, rotation) < 1e-2);
+        assert!(largest_force(FemSolver::Linear, rotation) > 10.0);
+    }
+}
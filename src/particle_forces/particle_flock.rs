@@ -0,0 +1,169 @@
+use crate::math::{Real, Vector3};
+use crate::particle::Particle;
+use crate::particle_forces::particle_group::ParticleGroup;
+use crate::particle_forces::particle_steering::{steer, towards};
+use crate::particle_forces::ParticleForceGenerator;
+use crate::spatial_hash::SpatialHash;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// The state of every member at the start of a step, with a spatial hash
+/// for finding each member's neighbours
+struct FlockSnapshot {
+    positions: Vec<Vector3>,
+    velocities: Vec<Vector3>,
+    hash: SpatialHash,
+}
+
+/// Generates flocking forces on a group of particles, combining Reynolds'
+/// separation, alignment and cohesion over each member's neighbours.
+/// A single generator holds the whole flock and is registered on every member.
+pub struct ParticleFlock {
+    group: ParticleGroup,
+
+    /// How far away other members are counted as neighbours
+    neighbour_radius: Real,
+
+    /// How close neighbours must be before a member steers away from them
+    separation_radius: Real,
+
+    separation_weight: Real,
+    alignment_weight: Real,
+    cohesion_weight: Real,
+
+    max_force: Real,
+    max_speed: Real,
+
+    /// Built at the first update of each step and discarded when it advances
+    snapshot: Option<FlockSnapshot>,
+}
+
+impl ParticleForceGenerator for ParticleFlock {
+    fn update_force(&mut self, particle: &mut Particle, _duration: Real) {
+        if !particle.has_finite_mass() {
+            return;
+        }
+
+        let snapshot = match self.snapshot.take() {
+            Some(snapshot) => snapshot,
+            None => self.take_snapshot(particle),
+        };
+
+        let position = particle.get_position();
+        let own_index = self.group.index_of(particle);
+
+        let mut away = Vector3::default();
+        let mut heading = Vector3::default();
+        let mut center = Vector3::default();
+        let mut count = 0;
+        for index in snapshot
+            .hash
+            .within(&snapshot.positions, &position, self.neighbour_radius)
+        {
+            if Some(index) == own_index {
+                continue;
+            }
+
+            // Separation pushes harder the closer the neighbour is
+            let offset = position - snapshot.positions[index];
+            let square_distance = offset.square_magnitude();
+            if square_distance > 0.0 && square_distance <= self.separation_radius.powi(2) {
+                away += offset * (1.0 / square_distance);
+            }
+
+            heading += snapshot.velocities[index];
+            center += snapshot.positions[index];
+            count += 1;
+        }
+
+        if count > 0 {
+            let mut force = Vector3::default();
+            if away.square_magnitude() > 0.0 {
+                let desired = towards(away, self.max_speed);
+                force += steer(particle, desired, self.max_force) * self.separation_weight;
+            }
+
+            let desired = towards(heading, self.max_speed);
+            force += steer(particle, desired, self.max_force) * self.alignment_weight;
+
+            let center = center * (1.0 / count as Real);
+            let desired = towards(center - position, self.max_speed);
+            force += steer(particle, desired, self.max_force) * self.cohesion_weight;
+
+            particle.add_force(&towards(force, force.magnitude().min(self.max_force)));
+        }
+
+        self.snapshot = Some(snapshot);
+    }
+
+    fn advance(&mut self, _duration: Real) {
+        self.snapshot = None;
+    }
+}
+
+impl ParticleFlock {
+    pub fn new(
+        members: Vec<Rc<RefCell<Particle>>>,
+        neighbour_radius: Real,
+        separation_radius: Real,
+        max_force: Real,
+        max_speed: Real,
+    ) -> Self {
+        Self {
+            group: ParticleGroup::new(members),
+            neighbour_radius,
+            separation_radius,
+            separation_weight: 1.5,
+            alignment_weight: 1.0,
+            cohesion_weight: 1.0,
+            max_force,
+            max_speed,
+            snapshot: None,
+        }
+    }
+
+    pub fn add_member(&mut self, member: Rc<RefCell<Particle>>) {
+        self.group.push(member);
+        self.snapshot = None;
+    }
+
+    pub fn get_members(&self) -> &[Rc<RefCell<Particle>>] {
+        self.group.members()
+    }
+
+    pub fn set_weights(&mut self, separation: Real, alignment: Real, cohesion: Real) {
+        self.separation_weight = separation;
+        self.alignment_weight = alignment;
+        self.cohesion_weight = cohesion;
+    }
+
+    /// Returns the members within a radius of a point.
+    /// This borrows every member, so can't be used while one is being updated.
+    pub fn neighbours(&self, point: &Vector3, radius: Real) -> Vec<Rc<RefCell<Particle>>> {
+        self.group
+            .members()
+            .iter()
+            .filter(|member| {
+                (member.borrow().get_position() - *point).square_magnitude() <= radius.powi(2)
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn take_snapshot(&self, particle: &Particle) -> FlockSnapshot {
+        let (positions, velocities): (Vec<_>, Vec<_>) = self
+            .group
+            .read(particle, |member| {
+                (member.get_position(), member.get_velocity())
+            })
+            .into_iter()
+            .unzip();
+
+        FlockSnapshot {
+            hash: SpatialHash::from_positions(self.neighbour_radius, &positions),
+            positions,
+            velocities,
+        }
+    }
+}
@@ -3,4 +3,17 @@ use crate::particle::Particle;
 
 pub trait ParticleForceGenerator {
     fn update_force(&mut self, particle: &mut Particle, duration: Real);
+
+    /// Advances any state that depends on simulation time.
+    /// Called once per step, after the forces have been applied,
+    /// no matter how many particles the generator is registered on.
+    fn advance(&mut self, _duration: Real) {}
+
+    /// Returns the potential energy this generator stores through the
+    /// given particle, if it reports one. Generators shared between several
+    /// particles report each particle's share, so that summing over every
+    /// registration gives the total.
+    fn potential_energy_of(&self, _particle: &Particle) -> Option<Real> {
+        None
+    }
 }
@@ -3,13 +3,18 @@ use crate::particle::Particle;
 use crate::particle_forces::ParticleForceGenerator;
 
 use std::cell::RefCell;
+use std::collections::HashSet;
 use std::rc::Rc;
 
+/// A particle and a force generator applied to it
+pub(crate) type Registration = (
+    Rc<RefCell<Particle>>,
+    Rc<RefCell<dyn ParticleForceGenerator>>,
+);
+
+#[derive(Default)]
 pub struct ParticleForceRegistry {
-    registrations: Vec<(
-        Rc<RefCell<Particle>>,
-        Rc<RefCell<dyn ParticleForceGenerator>>,
-    )>,
+    registrations: Vec<Registration>,
 }
 
 impl ParticleForceRegistry {
@@ -41,17 +46,109 @@ impl ParticleForceRegistry {
         }
     }
 
+    /// Removes every registration of a particle, e.g. when it is destroyed
+    pub fn remove_particle(&mut self, particle: &Rc<RefCell<Particle>>) {
+        self.registrations
+            .retain(|reg| !Rc::ptr_eq(&reg.0, particle));
+    }
+
+    /// Returns every registered particle once, in the order first registered
+    pub fn particles(&self) -> Vec<Rc<RefCell<Particle>>> {
+        let mut seen = HashSet::new();
+        self.registrations
+            .iter()
+            .filter(|reg| seen.insert(Rc::as_ptr(&reg.0)))
+            .map(|reg| Rc::clone(&reg.0))
+            .collect()
+    }
+
+    /// Sums the potential energy reported by every registration,
+    /// leaving out generators that don't report one
+    pub fn potential_energy(&self) -> Real {
+        self.registrations
+            .iter()
+            .filter_map(|reg| reg.1.borrow().potential_energy_of(&reg.0.borrow()))
+            .sum()
+    }
+
     pub fn clear(&mut self) {
         self.registrations.clear();
     }
 
+    /// Applies every generator to its particle, skipping sleeping particles
     pub fn update_forces(&mut self, duration: Real) {
         for registration in &self.registrations {
             let mut particle = registration.0.borrow_mut();
+            if particle.is_asleep() {
+                continue;
+            }
             registration
                 .1
                 .borrow_mut()
-                .update_force(&mut *particle, duration);
+                .update_force(&mut particle, duration);
+        }
+
+        // Advance each generator exactly once, even if it is shared
+        let mut advanced = HashSet::new();
+        for registration in &self.registrations {
+            if advanced.insert(Rc::as_ptr(&registration.1) as *const ()) {
+                registration.1.borrow_mut().advance(duration);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Vector3;
+    use crate::particle_forces::{ParticleAnchoredSpring, ParticleGravity};
+
+    /// Hangs a sleeping particle at rest on a spring, registering
+    /// gravity first or last, and returns its position and velocity
+    /// after a few steps
+    fn hang(gravity_first: bool) -> (Vector3, Vector3) {
+        let mut particle = Particle::default();
+        particle.set_mass(1.0);
+        particle.set_damping(1.0);
+        particle.set_sleep_threshold(0.1, 0.5);
+        particle.set_position(0.0, -2.0, 0.0);
+        particle.sleep();
+        let particle = Rc::new(RefCell::new(particle));
+
+        // The spring stretches by 1 to hold up the weight of 10
+        let spring = Rc::new(RefCell::new(ParticleAnchoredSpring::new(
+            Vector3::default(),
+            10.0,
+            1.0,
+        )));
+        let gravity = Rc::new(RefCell::new(ParticleGravity::new(Vector3::new(
+            0.0, -10.0, 0.0,
+        ))));
+
+        let mut registry = ParticleForceRegistry::new();
+        if gravity_first {
+            registry.add(particle.clone(), gravity);
+            registry.add(particle.clone(), spring);
+        } else {
+            registry.add(particle.clone(), spring);
+            registry.add(particle.clone(), gravity);
+        }
+
+        for _ in 0..10 {
+            registry.update_forces(0.1);
+            particle.borrow_mut().integrate(0.1);
+        }
+        let particle = particle.borrow();
+        (particle.get_position(), particle.get_velocity())
+    }
+
+    #[test]
+    fn sleeping_particles_stay_put_whatever_the_order() {
+        for &gravity_first in &[true, false] {
+            let (position, velocity) = hang(gravity_first);
+            assert_eq!(position.y, -2.0);
+            assert_eq!(velocity.magnitude(), 0.0);
         }
     }
 }
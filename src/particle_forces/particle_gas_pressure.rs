@@ -0,0 +1,177 @@
+use crate::math::{Real, Vector3};
+use crate::particle::Particle;
+use crate::particle_forces::particle_group::{ParticleGroup, StepForces};
+use crate::particle_forces::ParticleForceGenerator;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Generates the force of a gas sealed inside a closed triangle mesh of
+/// particles, pushing each face outwards. The pressure follows the ideal gas
+/// law, rising as the enclosed volume shrinks. The forces on every particle
+/// are found together at the first update of each step. A single generator
+/// holds the whole mesh and is registered on every particle in it.
+pub struct ParticleGasPressure {
+    group: ParticleGroup,
+
+    /// The faces of the mesh, wound anticlockwise when seen from outside
+    triangles: Vec<[usize; 3]>,
+
+    /// The product of pressure and volume, which stays constant
+    /// for a fixed amount of gas at a fixed temperature (nRT)
+    gas_amount: Real,
+
+    /// The volume and pressure as of the latest step
+    volume: Real,
+    pressure: Real,
+
+    forces: StepForces,
+}
+
+impl ParticleForceGenerator for ParticleGasPressure {
+    fn update_force(&mut self, particle: &mut Particle, _duration: Real) {
+        let index = match self.group.index_of(particle) {
+            Some(index) => index,
+            None => return,
+        };
+
+        if !self.forces.is_found() {
+            let forces = self.compute_forces(particle);
+            self.forces.set(forces);
+        }
+        self.forces.apply(index, particle);
+    }
+
+    fn advance(&mut self, _duration: Real) {
+        self.forces.clear();
+    }
+}
+
+impl ParticleGasPressure {
+    /// Creates a gas which has the given pressure at the mesh's current volume
+    pub fn new(
+        particles: Vec<Rc<RefCell<Particle>>>,
+        triangles: Vec<[usize; 3]>,
+        pressure: Real,
+    ) -> Self {
+        let positions: Vec<_> = particles
+            .iter()
+            .map(|particle| particle.borrow().get_position())
+            .collect();
+        let volume = enclosed_volume(&positions, &triangles);
+
+        Self {
+            group: ParticleGroup::new(particles),
+            triangles,
+            gas_amount: pressure * volume,
+            volume,
+            pressure,
+            forces: StepForces::default(),
+        }
+    }
+
+    /// Returns the enclosed volume as of the latest step
+    pub fn get_volume(&self) -> Real {
+        self.volume
+    }
+
+    /// Returns the gas pressure as of the latest step
+    pub fn get_pressure(&self) -> Real {
+        self.pressure
+    }
+
+    pub fn get_gas_amount(&self) -> Real {
+        self.gas_amount
+    }
+
+    /// Sets the amount of gas, e.g. to inflate or deflate the body
+    pub fn set_gas_amount(&mut self, gas_amount: Real) {
+        self.gas_amount = gas_amount;
+    }
+
+    fn compute_forces(&mut self, particle: &Particle) -> Vec<Vector3> {
+        let positions = self.group.read(particle, Particle::get_position);
+
+        self.volume = enclosed_volume(&positions, &self.triangles);
+        self.pressure = if self.volume > 0.0 {
+            self.gas_amount / self.volume
+        } else {
+            0.0
+        };
+
+        // Each face is pushed along its normal by the pressure over its area,
+        // shared equally between its corners. The cross product of two edges
+        // is the normal scaled by twice the area.
+        let mut forces = vec![Vector3::default(); positions.len()];
+        for &[a, b, c] in &self.triangles {
+            let doubled_area_normal = (positions[b] - positions[a]) % (positions[c] - positions[a]);
+            let corner_force = doubled_area_normal * (self.pressure / 6.0);
+            forces[a] += corner_force;
+            forces[b] += corner_force;
+            forces[c] += corner_force;
+        }
+        forces
+    }
+}
+
+/// Returns the volume enclosed by a closed mesh with outward facing triangles,
+/// by summing the signed volumes of the tetrahedra from the origin to each face
+pub fn enclosed_volume(positions: &[Vector3], triangles: &[[usize; 3]]) -> Real {
+    triangles
+        .iter()
+        .map(|&[a, b, c]| positions[a] * (positions[b] % positions[c]) / 6.0)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A unit right tetrahedron, with faces wound anticlockwise from outside
+    const TRIANGLES: [[usize; 3]; 4] = [[0, 2, 1], [0, 1, 3], [0, 3, 2], [1, 2, 3]];
+
+    fn corners() -> Vec<Rc<RefCell<Particle>>> {
+        [
+            (0.0, 0.0, 0.0),
+            (1.0, 0.0, 0.0),
+            (0.0, 1.0, 0.0),
+            (0.0, 0.0, 1.0),
+        ]
+        .iter()
+        .map(|&(x, y, z)| {
+            let mut particle = Particle::default();
+            particle.set_mass(1.0);
+            particle.set_position(x, y, z);
+            Rc::new(RefCell::new(particle))
+        })
+        .collect()
+    }
+
+    #[test]
+    fn pushes_every_corner_outwards() {
+        let corners = corners();
+        let mut gas = ParticleGasPressure::new(corners.clone(), TRIANGLES.to_vec(), 6.0);
+        assert!((gas.get_volume() - 1.0 / 6.0).abs() < 1e-6);
+
+        let mut total = Vector3::default();
+        for corner in &corners {
+            gas.update_force(&mut corner.borrow_mut(), 0.01);
+            total += corner.borrow().get_accumulated_force();
+        }
+        assert!(total.magnitude() < 1e-5);
+
+        // The origin corner is pushed away from the others
+        let origin = corners[0].borrow().get_accumulated_force();
+        assert!(origin.x < 0.0 && origin.y < 0.0 && origin.z < 0.0);
+    }
+
+    #[test]
+    fn pressure_rises_as_the_volume_shrinks() {
+        let corners = corners();
+        let mut gas = ParticleGasPressure::new(corners.clone(), TRIANGLES.to_vec(), 6.0);
+        corners[3].borrow_mut().set_position(0.0, 0.0, 0.5);
+        gas.update_force(&mut corners[0].borrow_mut(), 0.01);
+        assert!((gas.get_volume() - 1.0 / 12.0).abs() < 1e-6);
+        assert!((gas.get_pressure() - 12.0).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,591 @@
+use crate::attributes::Radius;
+use crate::math::{Real, Vector3};
+use crate::particle::Particle;
+use crate::particle_forces::particle_group::{ParticleGroup, StepForces};
+use crate::particle_forces::ParticleForceGenerator;
+use crate::spatial_hash::SpatialHash;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::f32::consts::PI;
+use std::rc::Rc;
+
+/// The ratio of tangential to normal contact stiffness,
+/// as for elastic spheres with a Poisson ratio near 0.3
+const TANGENTIAL_STIFFNESS_RATIO: Real = 2.0 / 7.0;
+
+/// The contact properties of a granular material
+#[derive(Clone, Copy)]
+pub struct GranularMaterial {
+    /// The spring constant resisting overlap between touching grains,
+    /// which must be high enough that fast grains cannot pass through
+    /// one another, with steps short enough to resolve each collision
+    pub stiffness: Real,
+
+    /// The ratio of separating to approaching speed after a collision
+    /// (0.0 = perfectly inelastic, 1.0 = perfectly elastic)
+    pub restitution: Real,
+
+    /// The ratio of the largest tangential force to the normal force
+    pub friction: Real,
+
+    /// The ratio of the torque resisting rolling to the normal force
+    /// times the grain radius, which lets round grains hold a pile
+    pub rolling_friction: Real,
+
+    /// The attractive force between touching grains (e.g. damp sand)
+    pub cohesion: Real,
+}
+
+impl GranularMaterial {
+    pub fn new(
+        stiffness: Real,
+        restitution: Real,
+        friction: Real,
+        rolling_friction: Real,
+        cohesion: Real,
+    ) -> Self {
+        assert!(
+            stiffness > 0.0,
+            "attempted to create a granular material with non-positive stiffness",
+        );
+        assert!(
+            restitution > 0.0 && restitution <= 1.0,
+            "attempted to create a granular material with restitution outside (0, 1]",
+        );
+
+        Self {
+            stiffness,
+            restitution,
+            friction,
+            rolling_friction,
+            cohesion,
+        }
+    }
+
+    pub fn sand() -> Self {
+        Self::new(1e5, 0.3, 0.6, 0.1, 0.0)
+    }
+
+    pub fn damp_sand() -> Self {
+        Self::new(1e5, 0.2, 0.7, 0.15, 5.0)
+    }
+
+    pub fn gravel() -> Self {
+        Self::new(5e5, 0.4, 0.8, 0.2, 0.0)
+    }
+
+    /// Returns the damping ratio giving contacts the material's restitution
+    fn damping_ratio(&self) -> Real {
+        let log = self.restitution.ln();
+        -log / (PI.powi(2) + log.powi(2)).sqrt()
+    }
+}
+
+/// What a grain is touching
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Contact {
+    Grain(usize),
+    Wall(usize),
+}
+
+/// A fixed plane which grains rest on, facing along its normal
+struct Wall {
+    normal: Vector3,
+    point: Vector3,
+}
+
+/// Generates the contact forces of a granular material such as sand or
+/// gravel, where each particle is a spherical grain, using the discrete
+/// element method. Touching grains are pushed apart by a spring-dashpot,
+/// held by friction and rolling resistance, and optionally stuck together.
+/// The grains' spins are tracked by the generator, since particles
+/// have none. Sleeping grains are woken when an awake grain strikes them.
+/// The forces on every grain are found together at the first update of
+/// each step. A single generator holds the whole material and is
+/// registered on every particle in it.
+pub struct ParticleGranular {
+    group: ParticleGroup,
+
+    radii: Vec<Real>,
+    material: GranularMaterial,
+    walls: Vec<Wall>,
+
+    /// The angular velocity of each grain
+    spins: Vec<Vector3>,
+
+    /// The torque on each grain, the inverse of its moment of inertia and
+    /// whether it is asleep for the current step, used to update the spins
+    /// as the step advances
+    torques: Vec<Vector3>,
+    inverse_inertias: Vec<Real>,
+    asleep: Vec<bool>,
+
+    /// How far each pair in contact has sheared since they touched,
+    /// which lets friction hold grains still rather than only slow them
+    shear: HashMap<(usize, Contact), Vector3>,
+
+    forces: StepForces,
+}
+
+impl ParticleForceGenerator for ParticleGranular {
+    fn update_force(&mut self, particle: &mut Particle, duration: Real) {
+        let index = match self.group.index_of(particle) {
+            Some(index) => index,
+            None => return,
+        };
+
+        if !self.forces.is_found() {
+            let forces = self.compute_forces(particle, duration);
+            self.forces.set(forces);
+        }
+        self.forces.apply(index, particle);
+    }
+
+    fn advance(&mut self, duration: Real) {
+        if !self.forces.clear() {
+            return;
+        }
+        for (((spin, torque), inverse_inertia), &asleep) in self
+            .spins
+            .iter_mut()
+            .zip(self.torques.iter())
+            .zip(self.inverse_inertias.iter())
+            .zip(self.asleep.iter())
+        {
+            if asleep {
+                *spin = Vector3::default();
+            } else {
+                spin.add_scaled_vector(torque, inverse_inertia * duration);
+            }
+        }
+    }
+}
+
+impl ParticleGranular {
+    /// Creates a material from grains with the given radii
+    pub fn new(
+        particles: Vec<Rc<RefCell<Particle>>>,
+        radii: Vec<Real>,
+        material: GranularMaterial,
+    ) -> Self {
+        assert_eq!(
+            particles.len(),
+            radii.len(),
+            "attempted to create a granular material with a radius per grain missing",
+        );
+        assert!(
+            radii.iter().all(|&radius| radius > 0.0),
+            "attempted to create a grain with a non-positive radius",
+        );
+
+        let count = particles.len();
+
+        Self {
+            group: ParticleGroup::new(particles),
+            radii,
+            material,
+            walls: Vec::new(),
+            spins: vec![Vector3::default(); count],
+            torques: vec![Vector3::default(); count],
+            inverse_inertias: vec![0.0; count],
+            asleep: vec![false; count],
+            shear: HashMap::new(),
+            forces: StepForces::default(),
+        }
+    }
+
+    /// Creates a material from grains which all have the same radius
+    pub fn uniform(
+        particles: Vec<Rc<RefCell<Particle>>>,
+        radius: Real,
+        material: GranularMaterial,
+    ) -> Self {
+        let radii = vec![radius; particles.len()];
+        Self::new(particles, radii, material)
+    }
+
+    /// Creates a material whose grains take their radius from their
+    /// `Radius` attribute, or the default radius if they have none
+    pub fn from_attributes(
+        particles: Vec<Rc<RefCell<Particle>>>,
+        default_radius: Real,
+        material: GranularMaterial,
+    ) -> Self {
+        let radii = particles
+            .iter()
+            .map(|particle| {
+                particle
+                    .borrow()
+                    .get_attribute::<Radius>()
+                    .map_or(default_radius, |radius| radius.0)
+            })
+            .collect();
+        Self::new(particles, radii, material)
+    }
+
+    /// Adds a fixed plane the grains collide with, e.g. the ground
+    pub fn add_wall(&mut self, normal: Vector3, point: Vector3) {
+        let mut normal = normal;
+        normal.normalize();
+        self.walls.push(Wall { normal, point });
+    }
+
+    /// Adds a horizontal floor at the given height
+    pub fn add_floor(&mut self, height: Real) {
+        self.add_wall(Vector3::new(0.0, 1.0, 0.0), Vector3::new(0.0, height, 0.0));
+    }
+
+    pub fn get_material(&self) -> GranularMaterial {
+        self.material
+    }
+
+    pub fn set_material(&mut self, material: GranularMaterial) {
+        self.material = material;
+    }
+
+    pub fn get_particles(&self) -> &[Rc<RefCell<Particle>>] {
+        self.group.members()
+    }
+
+    pub fn get_radius(&self, index: usize) -> Option<Real> {
+        self.radii.get(index).copied()
+    }
+
+    pub fn get_spin(&self, index: usize) -> Option<Vector3> {
+        self.spins.get(index).copied()
+    }
+
+    /// Returns the number of contacts as of the latest step
+    pub fn contact_count(&self) -> usize {
+        self.shear.len()
+    }
+
+    fn compute_forces(&mut self, particle: &Particle, duration: Real) -> Vec<Vector3> {
+        let states = self.group.read(particle, |member| {
+            (
+                member.get_position(),
+                member.get_velocity(),
+                member.get_inverse_mass(),
+                member.get_sleep_speed().unwrap_or(0.0),
+                member.is_asleep(),
+            )
+        });
+        let positions: Vec<_> = states.iter().map(|state| state.0).collect();
+        let velocities: Vec<_> = states.iter().map(|state| state.1).collect();
+        let inverse_masses: Vec<_> = states.iter().map(|state| state.2).collect();
+        let sleep_speeds: Vec<_> = states.iter().map(|state| state.3).collect();
+        self.asleep = states.iter().map(|state| state.4).collect();
+
+        // A solid sphere's moment of inertia is 2/5 m r^2
+        self.inverse_inertias = inverse_masses
+            .iter()
+            .zip(self.radii.iter())
+            .map(|(inverse_mass, radius)| inverse_mass * 2.5 / radius.powi(2))
+            .collect();
+
+        let count = positions.len();
+        let mut forces = vec![Vector3::default(); count];
+        self.torques = vec![Vector3::default(); count];
+        let mut shear = HashMap::with_capacity(self.shear.len());
+
+        // Sleeping grains struck by awake ones, which are woken
+        // once the forces are found
+        let mut struck = Vec::new();
+
+        let largest_radius = self.radii.iter().cloned().fold(0.0, Real::max);
+        let hash = SpatialHash::from_positions(2.0 * largest_radius, &positions);
+
+        for i in 0..count {
+            let mut touching = Vec::new();
+            hash.for_each_candidate(&positions[i], self.radii[i] + largest_radius, |j| {
+                if j > i {
+                    touching.push(j);
+                }
+            });
+
+            for j in touching {
+                let offset = positions[j] - positions[i];
+                let distance = offset.magnitude();
+                let overlap = self.radii[i] + self.radii[j] - distance;
+                if overlap <= 0.0 || distance == 0.0 {
+                    continue;
+                }
+
+                let normal = offset * (1.0 / distance);
+                let contact = ContactPoint {
+                    normal,
+                    overlap,
+                    radius: self.radii[i],
+                    other_radius: self.radii[j],
+                    relative_velocity: velocities[i] - velocities[j]
+                        + (self.spins[i] % normal) * self.radii[i]
+                        + (self.spins[j] % normal) * self.radii[j],
+                    relative_spin: self.spins[i] - self.spins[j],
+                    inverse_mass: inverse_masses[i] + inverse_masses[j],
+                    inverse_inertia: self.inverse_inertias[i] + self.inverse_inertias[j],
+                };
+                if self.asleep[i] != self.asleep[j] {
+                    let sleeper = if self.asleep[i] { i } else { j };
+                    if contact.relative_velocity.magnitude() > sleep_speeds[sleeper] {
+                        struck.push(sleeper);
+                    }
+                }
+
+                let key = (i, Contact::Grain(j));
+                let response = self.respond(&contact, self.shear.get(&key), duration);
+                shear.insert(key, response.shear);
+
+                forces[i] += response.force;
+                forces[j] -= response.force;
+                self.torques[i] += response.torque * self.radii[i] + response.rolling_torque;
+                self.torques[j] += response.torque * self.radii[j] - response.rolling_torque;
+            }
+
+            for (w, wall) in self.walls.iter().enumerate() {
+                let overlap = self.radii[i] - (positions[i] - wall.point) * wall.normal;
+                if overlap <= 0.0 {
+                    continue;
+                }
+
+                // The wall behaves as a still grain of infinite mass
+                // whose surface is the plane
+                let normal = wall.normal * -1.0;
+                let contact = ContactPoint {
+                    normal,
+                    overlap,
+                    radius: self.radii[i],
+                    other_radius: Real::INFINITY,
+                    relative_velocity: velocities[i] + (self.spins[i] % normal) * self.radii[i],
+                    relative_spin: self.spins[i],
+                    inverse_mass: inverse_masses[i],
+                    inverse_inertia: self.inverse_inertias[i],
+                };
+                let key = (i, Contact::Wall(w));
+                let response = self.respond(&contact, self.shear.get(&key), duration);
+                shear.insert(key, response.shear);
+
+                forces[i] += response.force;
+                self.torques[i] += response.torque * self.radii[i] + response.rolling_torque;
+            }
+        }
+
+        // Contacts which have separated are forgotten
+        self.shear = shear;
+
+        for index in struck {
+            let member = &self.group.members()[index];
+            if !std::ptr::eq(member.as_ptr(), particle) {
+                member.borrow_mut().wake();
+            }
+            self.asleep[index] = false;
+        }
+        forces
+    }
+
+    /// Finds the force and torques on the first of two touching grains
+    fn respond(
+        &self,
+        contact: &ContactPoint,
+        previous_shear: Option<&Vector3>,
+        duration: Real,
+    ) -> ContactResponse {
+        let material = &self.material;
+        let normal = contact.normal;
+        if contact.inverse_mass == 0.0 {
+            return ContactResponse::default();
+        }
+        let mass = 1.0 / contact.inverse_mass;
+        let damping_ratio = material.damping_ratio();
+
+        // The spring-dashpot pushes the grains apart, but never pulls them
+        // together, which is left to cohesion
+        let approach_speed = contact.relative_velocity * normal;
+        let normal_damping = 2.0 * damping_ratio * (mass * material.stiffness).sqrt();
+        let pressing =
+            (material.stiffness * contact.overlap + normal_damping * approach_speed).max(0.0);
+        let mut force = normal * (material.cohesion - pressing);
+
+        // Friction resists the grains shearing past one another like a
+        // spring, until it reaches its limit and they slip
+        let tangential_velocity = contact.relative_velocity - normal * approach_speed;
+        let mut shear = previous_shear.copied().unwrap_or_default();
+        shear -= normal * (shear * normal);
+        shear.add_scaled_vector(&tangential_velocity, duration);
+
+        let tangential_stiffness = TANGENTIAL_STIFFNESS_RATIO * material.stiffness;
+        let tangential_damping = 2.0 * damping_ratio * (mass * tangential_stiffness).sqrt();
+        let mut friction =
+            shear * -tangential_stiffness + tangential_velocity * -tangential_damping;
+        let limit = material.friction * pressing;
+        let magnitude = friction.magnitude();
+        if magnitude > limit {
+            friction *= limit / magnitude;
+            shear = friction * (-1.0 / tangential_stiffness);
+        }
+        force += friction;
+
+        // Rolling resistance opposes the grains' relative spin, but is
+        // limited so that it can stop the spin without reversing it
+        let mut rolling_torque = Vector3::default();
+        let spin = contact.relative_spin.magnitude();
+        if spin > 0.0 && contact.inverse_inertia > 0.0 {
+            let radius = effective_radius(contact.radius, contact.other_radius);
+            let stopping = spin / (contact.inverse_inertia * duration);
+            let resistance = (material.rolling_friction * radius * pressing).min(stopping);
+            rolling_torque = contact.relative_spin * (-resistance / spin);
+        }
+
+        ContactResponse {
+            force,
+            torque: normal % friction,
+            rolling_torque,
+            shear,
+        }
+    }
+}
+
+/// The geometry and relative motion of two touching grains,
+/// from the point of view of the first
+struct ContactPoint {
+    /// The direction from the first grain to the second
+    normal: Vector3,
+    overlap: Real,
+    radius: Real,
+    other_radius: Real,
+
+    /// The velocity of the first grain's surface relative
+    /// to the second's, at the point where they touch
+    relative_velocity: Vector3,
+    relative_spin: Vector3,
+
+    /// The sums of the grains' inverse masses and inverse moments of inertia
+    inverse_mass: Real,
+    inverse_inertia: Real,
+}
+
+#[derive(Default)]
+struct ContactResponse {
+    /// The force on the first grain, with the opposite acting on the second
+    force: Vector3,
+
+    /// The torque from friction on each grain, per unit of its radius
+    torque: Vector3,
+
+    /// The torque resisting rolling on the first grain,
+    /// with the opposite acting on the second
+    rolling_torque: Vector3,
+
+    /// The updated shear displacement of the contact
+    shear: Vector3,
+}
+
+/// Returns the radius of the sphere which rolls like a pair of spheres in contact
+fn effective_radius(radius: Real, other_radius: Real) -> Real {
+    if other_radius.is_infinite() {
+        radius
+    } else {
+        radius * other_radius / (radius + other_radius)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STEP: Real = 1e-4;
+
+    fn grain(x: Real, y: Real) -> Rc<RefCell<Particle>> {
+        let mut particle = Particle::default();
+        particle.set_mass(1.0);
+        particle.set_damping(1.0);
+        particle.set_position(x, y, 0.0);
+        Rc::new(RefCell::new(particle))
+    }
+
+    fn run(granular: &mut ParticleGranular, steps: usize) {
+        for _ in 0..steps {
+            for particle in granular.get_particles().to_vec() {
+                granular.update_force(&mut particle.borrow_mut(), STEP);
+            }
+            granular.advance(STEP);
+            for particle in granular.get_particles() {
+                let mut particle = particle.borrow_mut();
+                particle.integrate(STEP);
+                particle.clear_accumulator();
+            }
+        }
+    }
+
+    #[test]
+    fn bounces_with_the_material_restitution() {
+        let ball = grain(0.0, 0.11);
+        ball.borrow_mut().set_velocity(0.0, -1.0, 0.0);
+        let material = GranularMaterial::new(1e4, 0.5, 0.5, 0.0, 0.0);
+        let mut granular = ParticleGranular::uniform(vec![ball.clone()], 0.1, material);
+        granular.add_floor(0.0);
+
+        run(&mut granular, 1000);
+        assert_eq!(granular.contact_count(), 0);
+
+        // The dashpot never pulls the grain back as it leaves,
+        // so it bounces a little faster than the restitution
+        let speed = ball.borrow().get_velocity().y;
+        assert!(speed > 0.5 && speed < 0.6);
+    }
+
+    #[test]
+    fn stacked_grains_settle_on_the_floor() {
+        let grains = vec![grain(0.0, 0.1), grain(0.0, 0.3)];
+        for grain in &grains {
+            grain.borrow_mut().set_acceleration(0.0, -10.0, 0.0);
+        }
+        let material = GranularMaterial::new(1e4, 0.3, 0.5, 0.1, 0.0);
+        let mut granular = ParticleGranular::uniform(grains.clone(), 0.1, material);
+        granular.add_floor(0.0);
+
+        run(&mut granular, 10_000);
+        assert_eq!(granular.contact_count(), 2);
+        for grain in &grains {
+            assert!(grain.borrow().get_velocity().magnitude() < 1e-3);
+        }
+
+        // The top grain's weight squashes the bottom contact twice as far
+        let bottom = grains[0].borrow().get_position().y;
+        let top = grains[1].borrow().get_position().y;
+        assert!(((0.1 - bottom) - 2.0 * (0.2 - (top - bottom))).abs() < 1e-4);
+    }
+
+    #[test]
+    fn sliding_grains_start_to_roll() {
+        let ball = grain(0.0, 0.1);
+        ball.borrow_mut().set_velocity(1.0, 0.0, 0.0);
+        ball.borrow_mut().set_acceleration(0.0, -10.0, 0.0);
+        let material = GranularMaterial::new(1e4, 0.3, 0.5, 0.0, 0.0);
+        let mut granular = ParticleGranular::uniform(vec![ball.clone()], 0.1, material);
+        granular.add_floor(0.0);
+
+        run(&mut granular, 5000);
+
+        // Rolling along +x turns the ball clockwise about +z, and friction
+        // brings it to v = 5/7 of its sliding speed when it rolls without slipping
+        let spin = granular.get_spin(0).unwrap();
+        let velocity = ball.borrow().get_velocity();
+        assert!(spin.z < 0.0);
+        assert!((velocity.x - 5.0 / 7.0).abs() < 0.02);
+        assert!((velocity.x + spin.z * 0.1).abs() < 0.02);
+    }
+
+    #[test]
+    fn radii_come_from_attributes() {
+        let small = grain(0.0, 0.0);
+        small.borrow_mut().set_attribute(Radius(0.05));
+        let granular = ParticleGranular::from_attributes(
+            vec![small, grain(1.0, 0.0)],
+            0.2,
+            GranularMaterial::sand(),
+        );
+        assert_eq!(granular.get_radius(0), Some(0.05));
+        assert_eq!(granular.get_radius(1), Some(0.2));
+    }
+}
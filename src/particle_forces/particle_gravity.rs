@@ -13,6 +13,15 @@ impl ParticleForceGenerator for ParticleGravity {
             particle.add_force(&(self.gravity * particle.get_mass()));
         }
     }
+
+    /// Measured from the origin, rising against the direction of gravity
+    fn potential_energy_of(&self, particle: &Particle) -> Option<Real> {
+        if particle.has_finite_mass() {
+            Some(-particle.get_mass() * (self.gravity * particle.get_position()))
+        } else {
+            None
+        }
+    }
 }
 
 impl ParticleGravity {
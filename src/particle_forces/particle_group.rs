@@ -0,0 +1,88 @@
+use crate::math::Vector3;
+use crate::particle::Particle;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// The members of a force generator which holds a whole group of particles
+/// and is registered on every one of them
+pub(crate) struct ParticleGroup {
+    members: Vec<Rc<RefCell<Particle>>>,
+
+    /// Finds a member's index from the particle being updated
+    indices: HashMap<*const Particle, usize>,
+}
+
+impl ParticleGroup {
+    pub fn new(members: Vec<Rc<RefCell<Particle>>>) -> Self {
+        let indices = members
+            .iter()
+            .enumerate()
+            .map(|(index, member)| (member.as_ptr() as *const Particle, index))
+            .collect();
+
+        Self { members, indices }
+    }
+
+    pub fn push(&mut self, member: Rc<RefCell<Particle>>) {
+        self.indices
+            .insert(member.as_ptr() as *const Particle, self.members.len());
+        self.members.push(member);
+    }
+
+    pub fn members(&self) -> &[Rc<RefCell<Particle>>] {
+        &self.members
+    }
+
+    /// Returns the index of the member being updated, if it is in the group
+    pub fn index_of(&self, particle: &Particle) -> Option<usize> {
+        self.indices.get(&(particle as *const Particle)).copied()
+    }
+
+    /// Reads a value from every member in order. The registry has already
+    /// borrowed the particle being updated, so it is read directly instead.
+    pub fn read<T>(&self, particle: &Particle, read: impl Fn(&Particle) -> T) -> Vec<T> {
+        self.members
+            .iter()
+            .map(|member| {
+                if std::ptr::eq(member.as_ptr(), particle) {
+                    read(particle)
+                } else {
+                    read(&member.borrow())
+                }
+            })
+            .collect()
+    }
+}
+
+/// The force on each member of a group for the current step, found together
+/// at the first update of the step and discarded when the step advances
+#[derive(Default)]
+pub(crate) struct StepForces {
+    forces: Option<Vec<Vector3>>,
+}
+
+impl StepForces {
+    /// Returns whether the forces for the current step have been found
+    pub fn is_found(&self) -> bool {
+        self.forces.is_some()
+    }
+
+    pub fn set(&mut self, forces: Vec<Vector3>) {
+        self.forces = Some(forces);
+    }
+
+    /// Adds a member's force for the current step, if it has been found
+    pub fn apply(&self, index: usize, particle: &mut Particle) {
+        if let Some(force) = self.forces.as_ref().and_then(|forces| forces.get(index)) {
+            particle.add_force(force);
+        }
+    }
+
+    /// Discards the forces as the step advances,
+    /// returning whether any had been found
+    pub fn clear(&mut self) -> bool {
+        self.forces.take().is_some()
+    }
+}
@@ -0,0 +1,72 @@
+use crate::math::{Real, Vector3};
+use crate::particle::Particle;
+use crate::particle_forces::ParticleForceGenerator;
+
+/// Delivers an impulse to a particle over a single step,
+/// as the force which changes its momentum by that much
+pub struct ParticleImpulse {
+    /// The impulse waiting to be delivered at the next step, if any
+    pending: Option<Vector3>,
+
+    /// Whether the pending impulse reached a particle during this step
+    delivered: bool,
+}
+
+impl ParticleForceGenerator for ParticleImpulse {
+    fn update_force(&mut self, particle: &mut Particle, duration: Real) {
+        if let Some(impulse) = self.pending {
+            particle.add_force(&(impulse * (1.0 / duration)));
+            self.delivered = true;
+        }
+    }
+
+    fn advance(&mut self, _duration: Real) {
+        // Keep the impulse until a step has actually delivered it
+        if self.delivered {
+            self.pending = None;
+            self.delivered = false;
+        }
+    }
+}
+
+impl ParticleImpulse {
+    /// Creates a generator which delivers the impulse at the next step
+    pub fn new(impulse: Vector3) -> Self {
+        Self {
+            pending: Some(impulse),
+            delivered: false,
+        }
+    }
+
+    /// Queues another impulse, adding to any not yet delivered
+    pub fn trigger(&mut self, impulse: Vector3) {
+        self.pending = Some(self.pending.unwrap_or_default() + impulse);
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delivers_the_impulse_once() {
+        let mut particle = Particle::default();
+        particle.set_mass(2.0);
+        particle.set_damping(1.0);
+        let mut impulse = ParticleImpulse::new(Vector3::new(1.0, 0.0, 0.0));
+        impulse.trigger(Vector3::new(3.0, 0.0, 0.0));
+
+        for _ in 0..3 {
+            impulse.update_force(&mut particle, 0.1);
+            impulse.advance(0.1);
+            particle.integrate(0.1);
+            particle.clear_accumulator();
+        }
+        assert!(!impulse.is_pending());
+        assert!((particle.get_velocity().x - 2.0).abs() < 1e-5);
+    }
+}
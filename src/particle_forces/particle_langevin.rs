@@ -0,0 +1,161 @@
+use crate::math::{Random, Real, Vector3};
+use crate::particle::Particle;
+use crate::particle_forces::ParticleForceGenerator;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Holds a set of particles at a temperature by applying Langevin dynamics:
+/// a friction force draining energy, balanced by random kicks whose size
+/// depends on the temperature. A single generator can be registered on
+/// every particle in the set, and the same seed and order of updates
+/// always give the same motion.
+pub struct ParticleLangevin {
+    temperature: Real,
+
+    /// The rate at which the friction drains velocity, per second
+    friction: Real,
+
+    /// Relates temperature to energy (1.0 in reduced units)
+    boltzmann_constant: Real,
+
+    random: Random,
+}
+
+impl ParticleForceGenerator for ParticleLangevin {
+    fn update_force(&mut self, particle: &mut Particle, duration: Real) {
+        if !particle.has_finite_mass() {
+            return;
+        }
+
+        let mass = particle.get_mass();
+        let drag = particle.get_velocity() * (-self.friction * mass);
+
+        // The fluctuation-dissipation theorem sets the size of the kicks
+        // which balance the friction at the target temperature
+        let variance = 2.0 * self.friction * mass * self.boltzmann_constant * self.temperature;
+        let kick = self.random.gaussian_vector() * (variance / duration).sqrt();
+
+        particle.add_force(&(drag + kick));
+    }
+}
+
+impl ParticleLangevin {
+    pub fn new(temperature: Real, friction: Real, seed: u64) -> Self {
+        Self {
+            temperature,
+            friction,
+            boltzmann_constant: 1.0,
+            random: Random::new(seed),
+        }
+    }
+
+    pub fn get_temperature(&self) -> Real {
+        self.temperature
+    }
+
+    pub fn set_temperature(&mut self, temperature: Real) {
+        self.temperature = temperature;
+    }
+
+    pub fn set_friction(&mut self, friction: Real) {
+        self.friction = friction;
+    }
+
+    pub fn set_boltzmann_constant(&mut self, boltzmann_constant: Real) {
+        self.boltzmann_constant = boltzmann_constant;
+    }
+
+    /// Returns the temperature of a set of particles from their mean kinetic
+    /// energy, measured relative to their center of mass motion
+    pub fn measure_temperature(&self, particles: &[Rc<RefCell<Particle>>]) -> Real {
+        let (momentum, mass) = particles
+            .iter()
+            .map(|particle| particle.borrow())
+            .filter(|particle| particle.has_finite_mass())
+            .fold((Vector3::default(), 0.0), |(momentum, mass), particle| {
+                (
+                    momentum + particle.get_velocity() * particle.get_mass(),
+                    mass + particle.get_mass(),
+                )
+            });
+        if mass == 0.0 {
+            return 0.0;
+        }
+        let drift = momentum * (1.0 / mass);
+
+        let mut kinetic_energy = 0.0;
+        let mut count = 0;
+        for particle in particles.iter().map(|particle| particle.borrow()) {
+            if particle.has_finite_mass() {
+                let relative = particle.get_velocity() - drift;
+                kinetic_energy += 0.5 * particle.get_mass() * relative.square_magnitude();
+                count += 1;
+            }
+        }
+
+        // Each particle has three degrees of freedom holding kT/2 each
+        2.0 * kinetic_energy / (3.0 * count as Real * self.boltzmann_constant)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::particle_forces::ParticleForceRegistry;
+
+    const STEP: Real = 0.01;
+
+    /// Runs a gas at temperature 3 and returns its temperature
+    /// averaged over the second half of the run, and the final velocities
+    fn run(seed: u64) -> (Real, Vec<Vector3>) {
+        let langevin = Rc::new(RefCell::new(ParticleLangevin::new(3.0, 1.0, seed)));
+        let mut registry = ParticleForceRegistry::new();
+        let particles: Vec<_> = (0..100)
+            .map(|_| {
+                let mut particle = Particle::default();
+                particle.set_mass(1.0);
+                particle.set_damping(1.0);
+                let particle = Rc::new(RefCell::new(particle));
+                registry.add(particle.clone(), langevin.clone());
+                particle
+            })
+            .collect();
+
+        let mut total = 0.0;
+        for step in 0..2000 {
+            registry.update_forces(STEP);
+            for particle in &particles {
+                particle.borrow_mut().integrate(STEP);
+            }
+            if step >= 1000 {
+                total += langevin.borrow().measure_temperature(&particles);
+            }
+        }
+
+        let velocities = particles
+            .iter()
+            .map(|particle| particle.borrow().get_velocity())
+            .collect();
+        (total / 1000.0, velocities)
+    }
+
+    #[test]
+    fn settles_at_its_temperature() {
+        let (temperature, _) = run(1);
+        assert!((temperature - 3.0).abs() < 0.15, "{}", temperature);
+    }
+
+    #[test]
+    fn repeats_for_a_seed() {
+        let velocities = |seed| {
+            run(seed)
+                .1
+                .iter()
+                .map(|velocity| (velocity.x, velocity.y, velocity.z))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(velocities(7), velocities(7));
+        assert_ne!(velocities(7), velocities(8));
+    }
+}
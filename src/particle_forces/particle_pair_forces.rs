@@ -0,0 +1,263 @@
+use crate::math::{Real, Vector3};
+use crate::particle::Particle;
+use crate::particle_forces::particle_group::ParticleGroup;
+use crate::particle_forces::{PairPotential, ParticleForceGenerator, PeriodicBox};
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Generates the forces between every pair of a set of particles interacting
+/// through a pair potential, as in molecular dynamics. Pairs further apart
+/// than the cutoff are ignored, and the pairs within the cutoff plus a skin
+/// are kept in Verlet neighbour lists, which are only rebuilt once some
+/// particle has moved more than half the skin.
+/// A single generator holds the whole set and is registered on every member.
+pub struct ParticlePairForces {
+    group: ParticleGroup,
+
+    potential: PairPotential,
+    cutoff: Real,
+    skin: Real,
+
+    /// The potential energy at the cutoff, subtracted so the energy
+    /// doesn't jump as pairs cross it
+    energy_shift: Real,
+
+    /// If set, particles are wrapped into the box and interact
+    /// with the nearest image of each other particle
+    periodic_box: Option<PeriodicBox>,
+
+    /// The neighbours of each member, and the positions they were found at
+    neighbours: Vec<Vec<usize>>,
+    listed_positions: Vec<Vector3>,
+
+    /// Every member's position at the start of the step,
+    /// taken at the first update and discarded when the step advances
+    snapshot: Option<Vec<Vector3>>,
+}
+
+impl ParticleForceGenerator for ParticlePairForces {
+    fn update_force(&mut self, particle: &mut Particle, _duration: Real) {
+        let index = match self.group.index_of(particle) {
+            Some(index) => index,
+            None => return,
+        };
+
+        if let Some(periodic_box) = &self.periodic_box {
+            let wrapped = periodic_box.wrap(&particle.get_position());
+            particle.set_position(wrapped.x, wrapped.y, wrapped.z);
+        }
+
+        let positions = match self.snapshot.take() {
+            Some(positions) => positions,
+            None => {
+                let positions = self.take_snapshot(particle);
+                if self.needs_rebuild(&positions) {
+                    self.rebuild(&positions);
+                }
+                positions
+            }
+        };
+
+        let mut force = Vector3::default();
+        for &other in &self.neighbours[index] {
+            let difference = self.separation(&positions[index], &positions[other]);
+            let distance = difference.magnitude();
+            if distance > 0.0 && distance < self.cutoff {
+                force += difference * (self.potential.force(distance) / distance);
+            }
+        }
+        particle.add_force(&force);
+
+        self.snapshot = Some(positions);
+    }
+
+    fn advance(&mut self, _duration: Real) {
+        self.snapshot = None;
+    }
+
+    /// Half the energy of every pair within the cutoff that includes
+    /// the particle, as each pair is shared between its two members
+    fn potential_energy_of(&self, particle: &Particle) -> Option<Real> {
+        let index = self.group.index_of(particle)?;
+        let position = particle.get_position();
+
+        let mut energy = 0.0;
+        for (other, member) in self.group.members().iter().enumerate() {
+            if other == index {
+                continue;
+            }
+            let difference = self.separation(&position, &member.borrow().get_position());
+            let distance = difference.magnitude();
+            if distance > 0.0 && distance < self.cutoff {
+                energy += 0.5 * (self.potential.energy(distance) - self.energy_shift);
+            }
+        }
+        Some(energy)
+    }
+}
+
+impl ParticlePairForces {
+    pub fn new(
+        particles: Vec<Rc<RefCell<Particle>>>,
+        potential: PairPotential,
+        cutoff: Real,
+        skin: Real,
+    ) -> Self {
+        Self {
+            group: ParticleGroup::new(particles),
+            potential,
+            cutoff,
+            skin,
+            energy_shift: potential.energy(cutoff),
+            periodic_box: None,
+            neighbours: Vec::new(),
+            listed_positions: Vec::new(),
+            snapshot: None,
+        }
+    }
+
+    /// Creates a generator for particles in a periodic box,
+    /// which should be at least twice the cutoff plus skin across
+    pub fn periodic(
+        particles: Vec<Rc<RefCell<Particle>>>,
+        potential: PairPotential,
+        cutoff: Real,
+        skin: Real,
+        periodic_box: PeriodicBox,
+    ) -> Self {
+        let mut forces = Self::new(particles, potential, cutoff, skin);
+        forces.periodic_box = Some(periodic_box);
+        forces
+    }
+
+    pub fn get_particles(&self) -> &[Rc<RefCell<Particle>>] {
+        self.group.members()
+    }
+
+    /// Returns the indices of the members listed as neighbours of a member
+    pub fn neighbours_of(&self, index: usize) -> &[usize] {
+        self.neighbours
+            .get(index)
+            .map_or(&[], |list| list.as_slice())
+    }
+
+    /// Returns the total potential energy of every pair within the cutoff.
+    /// This borrows every member, so can't be used while one is being updated.
+    pub fn potential_energy(&self) -> Real {
+        let positions: Vec<_> = self
+            .group
+            .members()
+            .iter()
+            .map(|particle| particle.borrow().get_position())
+            .collect();
+
+        let mut energy = 0.0;
+        for (index, position) in positions.iter().enumerate() {
+            for other in &positions[index + 1..] {
+                let distance = self.separation(position, other).magnitude();
+                if distance > 0.0 && distance < self.cutoff {
+                    energy += self.potential.energy(distance) - self.energy_shift;
+                }
+            }
+        }
+        energy
+    }
+
+    /// Returns the vector from one position to another,
+    /// using the nearest image in a periodic box
+    fn separation(&self, to: &Vector3, from: &Vector3) -> Vector3 {
+        let difference = *to - *from;
+        match &self.periodic_box {
+            Some(periodic_box) => periodic_box.minimum_image(&difference),
+            None => difference,
+        }
+    }
+
+    fn needs_rebuild(&self, positions: &[Vector3]) -> bool {
+        if self.listed_positions.len() != positions.len() {
+            return true;
+        }
+
+        // No pair can have come within the cutoff unless a particle
+        // moved more than half the skin
+        let limit = (0.5 * self.skin).powi(2);
+        positions
+            .iter()
+            .zip(&self.listed_positions)
+            .any(|(now, then)| self.separation(now, then).square_magnitude() > limit)
+    }
+
+    fn rebuild(&mut self, positions: &[Vector3]) {
+        let reach = (self.cutoff + self.skin).powi(2);
+        let mut neighbours = vec![Vec::new(); positions.len()];
+        for (index, position) in positions.iter().enumerate() {
+            for (other, other_position) in positions.iter().enumerate().skip(index + 1) {
+                if self.separation(position, other_position).square_magnitude() <= reach {
+                    neighbours[index].push(other);
+                    neighbours[other].push(index);
+                }
+            }
+        }
+        self.neighbours = neighbours;
+        self.listed_positions = positions.to_vec();
+    }
+
+    fn take_snapshot(&self, particle: &Particle) -> Vec<Vector3> {
+        self.group.read(particle, |member| {
+            let position = member.get_position();
+            match &self.periodic_box {
+                Some(periodic_box) => periodic_box.wrap(&position),
+                None => position,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CUTOFF: Real = 2.5;
+    const SKIN: Real = 0.5;
+
+    fn particle_at(x: Real) -> Rc<RefCell<Particle>> {
+        let mut particle = Particle::default();
+        particle.set_mass(1.0);
+        particle.set_position(x, 0.0, 0.0);
+        Rc::new(RefCell::new(particle))
+    }
+
+    fn step(forces: &mut ParticlePairForces, particle: &Rc<RefCell<Particle>>) {
+        forces.update_force(&mut particle.borrow_mut(), 0.01);
+        forces.advance(0.01);
+    }
+
+    #[test]
+    fn neighbour_lists_rebuild_after_moving_half_the_skin() {
+        let near = particle_at(0.0);
+        let far = particle_at(CUTOFF + SKIN + 0.1);
+        let potential = PairPotential::LennardJones {
+            epsilon: 1.0,
+            sigma: 1.0,
+        };
+        let mut forces =
+            ParticlePairForces::new(vec![near.clone(), far.clone()], potential, CUTOFF, SKIN);
+
+        step(&mut forces, &near);
+        assert!(forces.neighbours_of(0).is_empty());
+
+        // Moving less than half the skin keeps the old lists,
+        // even though the pair is now within the cutoff plus skin
+        far.borrow_mut().set_position(CUTOFF + SKIN - 0.1, 0.0, 0.0);
+        step(&mut forces, &near);
+        assert!(forces.neighbours_of(0).is_empty());
+
+        // Moving further than half the skin from where the lists were
+        // built rebuilds them
+        far.borrow_mut().set_position(CUTOFF + 0.1, 0.0, 0.0);
+        step(&mut forces, &near);
+        assert_eq!(forces.neighbours_of(0), &[1]);
+        assert_eq!(forces.neighbours_of(1), &[0]);
+    }
+}
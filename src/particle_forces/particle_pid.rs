@@ -0,0 +1,166 @@
+use crate::math::{Real, Vector3};
+use crate::particle::Particle;
+use crate::particle_forces::ParticleForceGenerator;
+
+/// What a PID controlled particle tries to reach
+#[derive(Clone, Copy)]
+pub enum PidTarget {
+    Position(Vector3),
+    Velocity(Vector3),
+}
+
+/// The gains of each term of a PID controller
+#[derive(Clone, Copy)]
+pub struct PidGains {
+    pub proportional: Real,
+    pub integral: Real,
+    pub derivative: Real,
+}
+
+impl PidGains {
+    pub fn new(proportional: Real, integral: Real, derivative: Real) -> Self {
+        Self {
+            proportional,
+            integral,
+            derivative,
+        }
+    }
+}
+
+/// Generates a force steering a particle towards a target position or velocity
+/// using a proportional-integral-derivative controller.
+/// Since the controller keeps state about one particle,
+/// it should only be registered on one particle.
+pub struct ParticlePid {
+    target: PidTarget,
+    gains: PidGains,
+
+    /// The largest force the controller may apply
+    max_force: Real,
+
+    /// The largest magnitude the accumulated error may reach
+    max_integral: Real,
+
+    /// The error accumulated over time
+    integral: Vector3,
+
+    /// The velocity at the previous step, for differentiating in velocity mode
+    previous_velocity: Option<Vector3>,
+}
+
+impl ParticleForceGenerator for ParticlePid {
+    fn update_force(&mut self, particle: &mut Particle, duration: Real) {
+        let velocity = particle.get_velocity();
+
+        // The derivative is taken of the measurement rather than the error,
+        // so moving the target doesn't cause a sudden kick
+        let (error, derivative) = match self.target {
+            PidTarget::Position(target) => (target - particle.get_position(), velocity * -1.0),
+            PidTarget::Velocity(target) => {
+                let change = self
+                    .previous_velocity
+                    .map_or(Vector3::default(), |previous| velocity - previous);
+                (target - velocity, change * (-1.0 / duration))
+            }
+        };
+        self.previous_velocity = Some(velocity);
+
+        let unintegrated = error * self.gains.proportional + derivative * self.gains.derivative;
+
+        // Stop accumulating error while the output is saturated in the same
+        // direction, so the integral doesn't wind up while the force is capped
+        let candidate = self.integral + error * duration;
+        let saturated = (unintegrated + self.integral * self.gains.integral).magnitude()
+            >= self.max_force
+            && error * self.integral > 0.0;
+        if !saturated {
+            self.integral = clamp_magnitude(candidate, self.max_integral);
+        }
+
+        let force = unintegrated + self.integral * self.gains.integral;
+        particle.add_force(&clamp_magnitude(force, self.max_force));
+    }
+}
+
+impl ParticlePid {
+    pub fn new(target: PidTarget, gains: PidGains) -> Self {
+        Self {
+            target,
+            gains,
+            max_force: Real::INFINITY,
+            max_integral: Real::INFINITY,
+            integral: Vector3::default(),
+            previous_velocity: None,
+        }
+    }
+
+    pub fn get_target(&self) -> PidTarget {
+        self.target
+    }
+
+    /// Moves the target, e.g. to follow a moving object each frame
+    pub fn set_target(&mut self, target: PidTarget) {
+        self.target = target;
+    }
+
+    pub fn set_gains(&mut self, gains: PidGains) {
+        self.gains = gains;
+    }
+
+    pub fn set_max_force(&mut self, max_force: Real) {
+        self.max_force = max_force;
+    }
+
+    pub fn set_max_integral(&mut self, max_integral: Real) {
+        self.max_integral = max_integral;
+    }
+
+    /// Forgets the accumulated error and previous measurement
+    pub fn reset(&mut self) {
+        self.integral = Vector3::default();
+        self.previous_velocity = None;
+    }
+}
+
+fn clamp_magnitude(vector: Vector3, max_magnitude: Real) -> Vector3 {
+    let magnitude = vector.magnitude();
+    if magnitude > max_magnitude {
+        vector * (max_magnitude / magnitude)
+    } else {
+        vector
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integral_term_cancels_a_constant_disturbance() {
+        let target = Vector3::new(1.0, 2.0, 0.0);
+        let mut pid = ParticlePid::new(PidTarget::Position(target), PidGains::new(4.0, 2.0, 3.0));
+        let mut particle = Particle::default();
+        particle.set_mass(1.0);
+        particle.set_damping(1.0);
+        particle.set_acceleration(0.0, -2.0, 0.0);
+
+        for _ in 0..3000 {
+            pid.update_force(&mut particle, 0.01);
+            particle.integrate(0.01);
+            particle.clear_accumulator();
+        }
+        assert!((particle.get_position() - target).magnitude() < 0.01);
+        assert!(particle.get_velocity().magnitude() < 0.01);
+    }
+
+    #[test]
+    fn output_is_capped() {
+        let target = PidTarget::Velocity(Vector3::new(100.0, 0.0, 0.0));
+        let mut pid = ParticlePid::new(target, PidGains::new(10.0, 1.0, 0.0));
+        pid.set_max_force(5.0);
+        let mut particle = Particle::default();
+        particle.set_mass(1.0);
+        pid.update_force(&mut particle, 0.1);
+        assert!((particle.get_accumulated_force().x - 5.0).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,131 @@
+use crate::math::{Real, Vector3};
+use crate::particle::Particle;
+use crate::particle_forces::{FrictionMaterial, ParticleForceGenerator};
+
+/// Generates the support and friction forces on a particle
+/// resting or sliding on a fixed plane.
+/// The accumulated force is used to find how hard the particle presses
+/// on the plane, so this should be registered after the other generators
+/// acting on the particle.
+pub struct ParticlePlaneFriction {
+    /// The unit normal of the plane, pointing away from the solid side
+    normal: Vector3,
+
+    /// The distance of the plane from the origin along its normal
+    offset: Real,
+
+    /// The combined friction of the particle and plane materials
+    material: FrictionMaterial,
+
+    /// How far above the plane a particle is still considered to be touching it
+    contact_tolerance: Real,
+}
+
+impl ParticleForceGenerator for ParticlePlaneFriction {
+    fn update_force(&mut self, particle: &mut Particle, duration: Real) {
+        if !particle.has_finite_mass() {
+            return;
+        }
+
+        let height = particle.get_position() * self.normal - self.offset;
+        if height > self.contact_tolerance {
+            return;
+        }
+
+        let mass = particle.get_mass();
+        let velocity = particle.get_velocity();
+        let applied = particle.get_accumulated_force() + particle.get_acceleration() * mass;
+
+        // The plane pushes back against whatever presses the particle into it,
+        // and stops any motion into it
+        let normal_velocity = velocity * self.normal;
+        let mut support = -(applied * self.normal);
+        if normal_velocity < 0.0 {
+            support -= normal_velocity * mass / duration;
+        }
+        if support <= 0.0 {
+            return;
+        }
+        particle.add_force(&(self.normal * support));
+
+        // Friction resists motion along the plane in proportion to the support
+        let tangential_velocity = velocity - self.normal * normal_velocity;
+        let tangential_force = applied - self.normal * (applied * self.normal);
+        let friction = self.material.friction_force(
+            tangential_velocity,
+            tangential_force,
+            support,
+            mass,
+            duration,
+        );
+        particle.add_force(&friction);
+    }
+}
+
+impl ParticlePlaneFriction {
+    /// Creates a plane through the given point with the given normal,
+    /// with the combined friction of the particle and plane materials
+    pub fn new(normal: Vector3, point: Vector3, material: FrictionMaterial) -> Self {
+        let mut normal = normal;
+        normal.normalize();
+        Self {
+            normal,
+            offset: point * normal,
+            material,
+            contact_tolerance: 0.01,
+        }
+    }
+
+    /// Creates a horizontal floor at the given height above y=0
+    pub fn floor(height: Real, material: FrictionMaterial) -> Self {
+        Self::new(
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, height, 0.0),
+            material,
+        )
+    }
+
+    pub fn set_contact_tolerance(&mut self, contact_tolerance: Real) {
+        self.contact_tolerance = contact_tolerance;
+    }
+
+    pub fn get_material(&self) -> FrictionMaterial {
+        self.material
+    }
+
+    pub fn set_material(&mut self, material: FrictionMaterial) {
+        self.material = material;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Pushes a unit mass resting on a wooden floor under gravity,
+    /// and returns its velocity after a unit step
+    fn push(force: Real) -> Vector3 {
+        let mut particle = Particle::default();
+        particle.set_mass(1.0);
+        particle.set_damping(1.0);
+        particle.set_acceleration(0.0, -10.0, 0.0);
+        particle.add_force(&Vector3::new(force, 0.0, 0.0));
+
+        let mut floor = ParticlePlaneFriction::floor(0.0, FrictionMaterial::new(0.5, 0.3));
+        floor.update_force(&mut particle, 1.0);
+        particle.integrate(1.0);
+        particle.get_velocity()
+    }
+
+    #[test]
+    fn resting_particle_is_held() {
+        let velocity = push(2.0);
+        assert!(velocity.magnitude() < 1e-5);
+    }
+
+    #[test]
+    fn hard_push_slides_against_kinetic_friction() {
+        let velocity = push(8.0);
+        assert!((velocity.x - 5.0).abs() < 1e-5 && velocity.y.abs() < 1e-5);
+    }
+}
@@ -0,0 +1,102 @@
+use crate::math::Real;
+use crate::particle::Particle;
+use crate::particle_forces::ParticleForceGenerator;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Generates the force holding a particle at a fixed distance from another,
+/// like a stiff rod or chain link. Like the fake spring, it works out the
+/// force needed over the step rather than modelling a material, changing the
+/// relative velocity along the rod to close any error in its length.
+/// It should be registered on both ends so momentum is conserved.
+pub struct ParticleRod {
+    other: Rc<RefCell<Particle>>,
+    length: Real,
+
+    /// The proportion of the needed correction applied each step.
+    /// Values below 1.0 keep chains of rods from overcorrecting,
+    /// since each particle is pulled on by several rods at once.
+    relaxation: Real,
+}
+
+impl ParticleForceGenerator for ParticleRod {
+    fn update_force(&mut self, particle: &mut Particle, duration: Real) {
+        let other = self.other.borrow();
+        let inverse_mass = particle.get_inverse_mass() + other.get_inverse_mass();
+        if inverse_mass == 0.0 {
+            return;
+        }
+
+        // Particles move by their current velocity before it is updated,
+        // so aim to correct the separation they will have after this step
+        let relative_velocity = particle.get_velocity() - other.get_velocity();
+        let predicted =
+            particle.get_position() - other.get_position() + relative_velocity * duration;
+        let distance = predicted.magnitude();
+        if distance == 0.0 {
+            return;
+        }
+        let direction = predicted * (1.0 / distance);
+
+        // Find the change in the relative velocity along the rod
+        // which would bring it back to length over the following step
+        let change = (self.length - distance) / duration * self.relaxation;
+
+        // Share the change between the ends according to their masses
+        let magnitude = change / (inverse_mass * duration);
+        particle.add_force(&(direction * magnitude));
+    }
+}
+
+impl ParticleRod {
+    pub fn new(other: Rc<RefCell<Particle>>, length: Real) -> Self {
+        Self {
+            other,
+            length,
+            relaxation: 0.8,
+        }
+    }
+
+    pub fn get_length(&self) -> Real {
+        self.length
+    }
+
+    pub fn set_length(&mut self, length: Real) {
+        self.length = length;
+    }
+
+    pub fn set_relaxation(&mut self, relaxation: Real) {
+        self.relaxation = relaxation;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn holds_the_ends_at_its_length() {
+        let mut anchor = Particle::default();
+        anchor.set_inverse_mass(0.0);
+        let anchor = Rc::new(RefCell::new(anchor));
+
+        let mut particle = Particle::default();
+        particle.set_mass(1.0);
+        particle.set_damping(1.0);
+        particle.set_position(1.0, 0.0, 0.0);
+        particle.set_acceleration(0.0, -10.0, 0.0);
+
+        let mut rod = ParticleRod::new(anchor, 1.0);
+        for _ in 0..1000 {
+            rod.update_force(&mut particle, 0.01);
+            particle.integrate(0.01);
+            particle.clear_accumulator();
+        }
+
+        // The particle swings like a pendulum without stretching the rod
+        let position = particle.get_position();
+        assert!((position.magnitude() - 1.0).abs() < 0.02);
+        assert!(position.y < 0.0);
+    }
+}
@@ -0,0 +1,220 @@
+use crate::math::{Real, Vector3};
+use crate::particle::Particle;
+use crate::particle_forces::particle_group::{ParticleGroup, StepForces};
+use crate::particle_forces::{sph_kernels, ParticleForceGenerator};
+use crate::spatial_hash::SpatialHash;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// The length of color field gradient below which a particle
+/// is treated as inside the fluid, feeling no surface tension
+const SURFACE_THRESHOLD: Real = 1e-3;
+
+/// Generates the pressure, viscosity and surface tension forces of a fluid
+/// made from a set of particles, using smoothed-particle hydrodynamics.
+/// The forces on every particle are found together at the first update
+/// of each step. A single generator holds the whole fluid and is
+/// registered on every particle in it.
+pub struct ParticleSph {
+    group: ParticleGroup,
+
+    /// The distance over which each particle's properties are smoothed
+    smoothing_radius: Real,
+
+    /// The density the fluid settles at (e.g. water = 1000 kg/m^3)
+    rest_density: Real,
+
+    /// How strongly pressure resists compression above the rest density
+    stiffness: Real,
+
+    viscosity: Real,
+    surface_tension: Real,
+
+    /// The density and pressure at each particle, as of the latest step
+    densities: Vec<Real>,
+    pressures: Vec<Real>,
+
+    forces: StepForces,
+}
+
+impl ParticleForceGenerator for ParticleSph {
+    fn update_force(&mut self, particle: &mut Particle, _duration: Real) {
+        let index = match self.group.index_of(particle) {
+            Some(index) => index,
+            None => return,
+        };
+
+        if !self.forces.is_found() {
+            let forces = self.compute_forces(particle);
+            self.forces.set(forces);
+        }
+        self.forces.apply(index, particle);
+    }
+
+    fn advance(&mut self, _duration: Real) {
+        self.forces.clear();
+    }
+}
+
+impl ParticleSph {
+    pub fn new(
+        particles: Vec<Rc<RefCell<Particle>>>,
+        smoothing_radius: Real,
+        rest_density: Real,
+        stiffness: Real,
+        viscosity: Real,
+        surface_tension: Real,
+    ) -> Self {
+        Self {
+            group: ParticleGroup::new(particles),
+            smoothing_radius,
+            rest_density,
+            stiffness,
+            viscosity,
+            surface_tension,
+            densities: Vec::new(),
+            pressures: Vec::new(),
+            forces: StepForces::default(),
+        }
+    }
+
+    /// Creates a water-like fluid at the given smoothing radius
+    pub fn water(particles: Vec<Rc<RefCell<Particle>>>, smoothing_radius: Real) -> Self {
+        Self::new(particles, smoothing_radius, 1000.0, 3.0, 3.5, 0.0728)
+    }
+
+    pub fn get_particles(&self) -> &[Rc<RefCell<Particle>>] {
+        self.group.members()
+    }
+
+    /// Returns the density at a particle as of the latest step
+    pub fn density_of(&self, index: usize) -> Option<Real> {
+        self.densities.get(index).copied()
+    }
+
+    /// Returns the pressure at a particle as of the latest step
+    pub fn pressure_of(&self, index: usize) -> Option<Real> {
+        self.pressures.get(index).copied()
+    }
+
+    /// Returns the mass each particle should have for the fluid
+    /// to fill a volume at its rest density
+    pub fn particle_mass(&self, volume: Real, count: usize) -> Real {
+        self.rest_density * volume / count as Real
+    }
+
+    fn compute_forces(&mut self, particle: &Particle) -> Vec<Vector3> {
+        let h = self.smoothing_radius;
+
+        let states = self.group.read(particle, |member| {
+            (
+                member.get_position(),
+                member.get_velocity(),
+                member.get_mass(),
+            )
+        });
+        let positions: Vec<_> = states.iter().map(|state| state.0).collect();
+        let velocities: Vec<_> = states.iter().map(|state| state.1).collect();
+        let masses: Vec<_> = states.iter().map(|state| state.2).collect();
+
+        let hash = SpatialHash::from_positions(h, &positions);
+        let neighbours: Vec<_> = positions
+            .iter()
+            .map(|position| hash.within(&positions, position, h))
+            .collect();
+
+        // Density is the smoothed mass nearby, including the particle's own.
+        // Pressure only resists compression, leaving cohesion to surface tension.
+        self.densities = neighbours
+            .iter()
+            .enumerate()
+            .map(|(i, list)| {
+                list.iter()
+                    .map(|&j| {
+                        masses[j] * sph_kernels::poly6((positions[i] - positions[j]).magnitude(), h)
+                    })
+                    .sum()
+            })
+            .collect();
+        self.pressures = self
+            .densities
+            .iter()
+            .map(|density| (self.stiffness * (density - self.rest_density)).max(0.0))
+            .collect();
+
+        let densities = &self.densities;
+        let pressures = &self.pressures;
+        neighbours
+            .iter()
+            .enumerate()
+            .map(|(i, list)| {
+                let mut pressure = Vector3::default();
+                let mut viscosity = Vector3::default();
+                let mut color_gradient = Vector3::default();
+                let mut color_laplacian = 0.0;
+
+                for &j in list {
+                    let offset = positions[i] - positions[j];
+                    let distance = offset.magnitude();
+                    let volume = masses[j] / densities[j];
+
+                    color_gradient += sph_kernels::poly6_gradient(&offset, h) * volume;
+                    color_laplacian += sph_kernels::poly6_laplacian(distance, h) * volume;
+                    if j == i {
+                        continue;
+                    }
+
+                    let shared_pressure = 0.5 * (pressures[i] + pressures[j]);
+                    pressure +=
+                        sph_kernels::spiky_gradient(&offset, h) * (-volume * shared_pressure);
+                    viscosity += (velocities[j] - velocities[i])
+                        * (volume * sph_kernels::viscosity_laplacian(distance, h));
+                }
+
+                let mut density_force = pressure + viscosity * self.viscosity;
+
+                // Surface tension pulls the surface flat, acting only
+                // where the color field changes sharply
+                let gradient_length = color_gradient.magnitude();
+                if gradient_length > SURFACE_THRESHOLD {
+                    density_force += color_gradient
+                        * (-self.surface_tension * color_laplacian / gradient_length);
+                }
+
+                // The forces above are per unit volume
+                density_force * (masses[i] / densities[i])
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn particle_at(x: Real) -> Rc<RefCell<Particle>> {
+        let mut particle = Particle::default();
+        particle.set_mass(1.0);
+        particle.set_position(x, 0.0, 0.0);
+        Rc::new(RefCell::new(particle))
+    }
+
+    #[test]
+    fn compressed_pair_is_pushed_apart() {
+        let left = particle_at(0.0);
+        let right = particle_at(0.1);
+        let mut sph = ParticleSph::new(vec![left.clone(), right.clone()], 0.5, 1.0, 10.0, 0.0, 0.0);
+        sph.update_force(&mut left.borrow_mut(), 0.01);
+        sph.update_force(&mut right.borrow_mut(), 0.01);
+
+        let left_force = left.borrow().get_accumulated_force();
+        let right_force = right.borrow().get_accumulated_force();
+        assert!(left_force.x < 0.0 && right_force.x > 0.0);
+        assert!((left_force + right_force).magnitude() < 1e-3 * left_force.magnitude());
+
+        // Each density counts both particles at their separation
+        let expected = sph_kernels::poly6(0.0, 0.5) + sph_kernels::poly6(0.1, 0.5);
+        assert!((sph.density_of(0).unwrap() - expected).abs() < 1e-3 * expected);
+    }
+}
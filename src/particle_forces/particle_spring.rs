@@ -1,5 +1,6 @@
 use crate::math::Real;
 use crate::particle::Particle;
+use crate::particle_forces::spring_damping::{self, SpringDamping};
 use crate::particle_forces::ParticleForceGenerator;
 
 use std::cell::RefCell;
@@ -10,11 +11,28 @@ pub struct ParticleSpring {
     other: Rc<RefCell<Particle>>,
     spring_constant: Real,
     rest_length: Real,
+
+    /// The force opposing the relative velocity along the spring, per unit speed
+    damping: Real,
+
+    /// If set, the spring breaks once stretched past this proportion of
+    /// its rest length (e.g. 0.5 = 150% of rest length)
+    breaking_strain: Option<Real>,
+    broken: bool,
+
+    /// Whether a matching spring is registered on the other particle,
+    /// so each end reports half the spring's energy
+    two_sided: bool,
 }
 
 impl ParticleForceGenerator for ParticleSpring {
     fn update_force(&mut self, particle: &mut Particle, _duration: Real) {
-        let difference = particle.get_position() - self.other.borrow_mut().get_position();
+        if self.broken {
+            return;
+        }
+
+        let other = self.other.borrow();
+        let difference = particle.get_position() - other.get_position();
         let direction = {
             let mut temp = difference;
             temp.normalize();
@@ -22,19 +40,216 @@ impl ParticleForceGenerator for ParticleSpring {
         };
         let distance = difference.magnitude();
 
+        if let Some(breaking_strain) = self.breaking_strain {
+            if distance - self.rest_length > breaking_strain * self.rest_length {
+                self.broken = true;
+                return;
+            }
+        }
+
         // A spring acts to pull the particle towards the rest length
         // with force proportional to the spring constant
-        let magnitude = self.spring_constant * (distance - self.rest_length).abs();
+        let mut magnitude = self.spring_constant * (distance - self.rest_length);
+
+        // The damper resists the particles moving apart or together
+        let relative_velocity = particle.get_velocity() - other.get_velocity();
+        magnitude += self.damping * (relative_velocity * direction);
+
         particle.add_force(&(direction * -magnitude));
     }
+
+    /// The spring's energy, halved if it is two-sided
+    fn potential_energy_of(&self, particle: &Particle) -> Option<Real> {
+        if self.broken {
+            return Some(0.0);
+        }
+
+        let extension = (particle.get_position() - self.other.borrow().get_position()).magnitude()
+            - self.rest_length;
+        Some(self.energy_share() * self.spring_constant * extension.powi(2))
+    }
 }
 
 impl ParticleSpring {
+    /// Creates a spring pulling the particle it is registered on towards
+    /// the other. It is two-sided, expecting a matching spring registered
+    /// on the other particle, unless `set_two_sided` says otherwise.
     pub fn new(other: Rc<RefCell<Particle>>, spring_constant: Real, rest_length: Real) -> Self {
+        Self::with_damping(other, spring_constant, 0.0, rest_length)
+    }
+
+    /// Creates a damped spring, which is two-sided as `new` says
+    pub fn with_damping(
+        other: Rc<RefCell<Particle>>,
+        spring_constant: Real,
+        damping: Real,
+        rest_length: Real,
+    ) -> Self {
         Self {
             other,
             spring_constant,
             rest_length,
+            damping,
+            breaking_strain: None,
+            broken: false,
+            two_sided: true,
+        }
+    }
+
+    /// Creates a spring whose constants give the two particles the given
+    /// natural frequency (in hertz) and damping ratio when the spring
+    /// is registered on both of them
+    pub fn from_frequency(
+        particle: &Particle,
+        other: Rc<RefCell<Particle>>,
+        frequency: Real,
+        damping_ratio: Real,
+        rest_length: Real,
+    ) -> Self {
+        let mass = spring_damping::reduced_mass(particle, &other.borrow());
+        let (spring_constant, damping) =
+            spring_damping::spring_constants(mass, frequency, damping_ratio);
+        Self::with_damping(other, spring_constant, damping, rest_length)
+    }
+
+    pub fn get_spring_constant(&self) -> Real {
+        self.spring_constant
+    }
+
+    pub fn get_damping(&self) -> Real {
+        self.damping
+    }
+
+    /// Sets whether a matching spring is registered on the other particle.
+    /// A one-sided spring, registered on a single end, reports all of
+    /// its energy rather than half.
+    pub fn set_two_sided(&mut self, two_sided: bool) {
+        self.two_sided = two_sided;
+    }
+
+    /// The part of 1/2 k x^2 reported by this end
+    fn energy_share(&self) -> Real {
+        if self.two_sided {
+            0.25
+        } else {
+            0.5
         }
     }
+
+    pub fn get_rest_length(&self) -> Real {
+        self.rest_length
+    }
+
+    /// Makes the spring break once stretched past the given proportion
+    /// of its rest length, or never if `None`
+    pub fn set_breaking_strain(&mut self, breaking_strain: Option<Real>) {
+        self.breaking_strain = breaking_strain;
+    }
+
+    pub fn is_broken(&self) -> bool {
+        self.broken
+    }
+
+    /// Breaks the spring, so it no longer applies any force
+    pub fn break_spring(&mut self) {
+        self.broken = true;
+    }
+
+    /// Returns the damping ratio of the spring between the given particle and the other
+    pub fn damping_ratio(&self, particle: &Particle) -> Real {
+        let mass = spring_damping::reduced_mass(particle, &self.other.borrow());
+        spring_damping::damping_ratio(mass, self.spring_constant, self.damping)
+    }
+
+    /// Classifies the spring's motion when integrated over steps of the given duration
+    pub fn damping_at(&self, particle: &Particle, duration: Real) -> SpringDamping {
+        let mass = spring_damping::reduced_mass(particle, &self.other.borrow());
+        SpringDamping::from_step(
+            mass,
+            self.spring_constant,
+            self.damping,
+            particle.get_damping(),
+            duration,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Applies a spring to a particle at the given offset from the other end,
+    /// and returns the velocity it gains over a unit step with unit mass
+    fn kick(x: Real, y: Real, z: Real) -> (Real, Real, Real) {
+        let other = Rc::new(RefCell::new(Particle::default()));
+        let mut particle = Particle::default();
+        particle.set_mass(1.0);
+        particle.set_damping(1.0);
+        particle.set_position(x, y, z);
+
+        ParticleSpring::new(other, 10.0, 2.0).update_force(&mut particle, 0.1);
+        particle.integrate(1.0);
+        let velocity = particle.get_velocity();
+        (velocity.x, velocity.y, velocity.z)
+    }
+
+    #[test]
+    fn stretched_springs_pull_and_compressed_springs_push() {
+        assert_eq!(kick(3.0, 0.0, 0.0), (-10.0, 0.0, 0.0));
+        assert_eq!(kick(1.0, 0.0, 0.0), (10.0, 0.0, 0.0));
+        assert_eq!(kick(0.0, 0.0, -2.0), (0.0, 0.0, 0.0));
+        assert_eq!(kick(0.0, 0.0, -1.0), (0.0, 0.0, -10.0));
+    }
+
+    #[test]
+    fn frequency_constructor_uses_the_reduced_mass() {
+        let mut particle = Particle::default();
+        particle.set_mass(2.0);
+        particle.set_damping(1.0);
+        let mut other = Particle::default();
+        other.set_mass(2.0);
+        let other = Rc::new(RefCell::new(other));
+
+        let spring = ParticleSpring::from_frequency(&particle, other, 1.0, 0.5, 1.0);
+        assert!((spring.damping_ratio(&particle) - 0.5).abs() < 1e-5);
+
+        // Two masses of 2 oscillate like a single mass of 1
+        let angular_frequency = 2.0 * std::f32::consts::PI;
+        assert!((spring.get_spring_constant() - angular_frequency.powi(2)).abs() < 1e-3);
+        assert_eq!(
+            spring.damping_at(&particle, 1e-3),
+            SpringDamping::Underdamped
+        );
+    }
+
+    #[test]
+    fn breaks_once_stretched_past_its_strain() {
+        let other = Rc::new(RefCell::new(Particle::default()));
+        let mut spring = ParticleSpring::new(other, 10.0, 2.0);
+        spring.set_breaking_strain(Some(0.5));
+
+        let mut particle = Particle::default();
+        particle.set_mass(1.0);
+        particle.set_position(2.9, 0.0, 0.0);
+        spring.update_force(&mut particle, 0.1);
+        assert!(!spring.is_broken());
+
+        particle.clear_accumulator();
+        particle.set_position(3.1, 0.0, 0.0);
+        spring.update_force(&mut particle, 0.1);
+        assert!(spring.is_broken());
+        assert_eq!(particle.get_accumulated_force().x, 0.0);
+    }
+
+    #[test]
+    fn one_sided_springs_report_all_their_energy() {
+        let other = Rc::new(RefCell::new(Particle::default()));
+        let mut particle = Particle::default();
+        particle.set_position(3.0, 0.0, 0.0);
+
+        let mut spring = ParticleSpring::new(other, 10.0, 2.0);
+        assert_eq!(spring.potential_energy_of(&particle), Some(2.5));
+        spring.set_two_sided(false);
+        assert_eq!(spring.potential_energy_of(&particle), Some(5.0));
+    }
 }
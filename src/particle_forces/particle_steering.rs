@@ -0,0 +1,224 @@
+use crate::math::{Random, Real, Vector3};
+use crate::particle::Particle;
+use crate::particle_forces::ParticleForceGenerator;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// A goal-seeking behaviour for a steered particle
+pub enum SteeringBehavior {
+    /// Heads straight for a point at full speed
+    Seek(Vector3),
+
+    /// Heads straight away from a point at full speed
+    Flee(Vector3),
+
+    /// Heads for a point, slowing down to stop on it
+    /// once within the slowing radius
+    Arrive {
+        target: Vector3,
+        slowing_radius: Real,
+    },
+
+    /// Heads for where another particle will be,
+    /// predicted from its current velocity
+    Pursue(Rc<RefCell<Particle>>),
+
+    /// Drifts about randomly, steering towards a point which jitters around
+    /// a circle projected ahead of the particle
+    Wander {
+        /// How far ahead of the particle the circle is projected
+        distance: Real,
+        radius: Real,
+
+        /// How far the point on the circle may move each second
+        jitter: Real,
+    },
+}
+
+/// Generates a steering force on a particle, in the style of Reynolds'
+/// steering behaviours, limited to a maximum force and speed.
+/// Wandering keeps state about one particle, so a wandering generator
+/// should only be registered on one particle.
+pub struct ParticleSteering {
+    behavior: SteeringBehavior,
+    max_force: Real,
+    max_speed: Real,
+
+    /// The point on the wander circle, relative to its center
+    wander_target: Vector3,
+    random: Random,
+}
+
+impl ParticleForceGenerator for ParticleSteering {
+    fn update_force(&mut self, particle: &mut Particle, duration: Real) {
+        if !particle.has_finite_mass() {
+            return;
+        }
+
+        let position = particle.get_position();
+        let velocity = particle.get_velocity();
+
+        let desired = match &self.behavior {
+            SteeringBehavior::Seek(target) => towards(*target - position, self.max_speed),
+            SteeringBehavior::Flee(target) => towards(position - *target, self.max_speed),
+            SteeringBehavior::Arrive {
+                target,
+                slowing_radius,
+            } => {
+                let offset = *target - position;
+                let distance = offset.magnitude();
+                let speed = self.max_speed * (distance / slowing_radius).min(1.0);
+                towards(offset, speed)
+            }
+            SteeringBehavior::Pursue(quarry) => {
+                // A particle can't pursue itself, and is already borrowed
+                if std::ptr::eq(quarry.as_ptr(), particle) {
+                    return;
+                }
+
+                let quarry = quarry.borrow();
+                let offset = quarry.get_position() - position;
+
+                // Look further ahead the longer it will take to get there
+                let lookahead = offset.magnitude() / self.max_speed;
+                let predicted = quarry.get_position() + quarry.get_velocity() * lookahead;
+                towards(predicted - position, self.max_speed)
+            }
+            SteeringBehavior::Wander {
+                distance,
+                radius,
+                jitter,
+            } => {
+                let nudge = self.random.unit_vector() * (jitter * duration);
+                self.wander_target = towards(self.wander_target + nudge, *radius);
+                let ahead = towards(velocity, *distance);
+                towards(ahead + self.wander_target, self.max_speed)
+            }
+        };
+
+        particle.add_force(&steer(particle, desired, self.max_force));
+    }
+}
+
+impl ParticleSteering {
+    pub fn new(behavior: SteeringBehavior, max_force: Real, max_speed: Real) -> Self {
+        Self::with_seed(behavior, max_force, max_speed, 0)
+    }
+
+    /// Creates a generator whose wandering follows the given random seed
+    pub fn with_seed(
+        behavior: SteeringBehavior,
+        max_force: Real,
+        max_speed: Real,
+        seed: u64,
+    ) -> Self {
+        assert_positive_speed(max_speed);
+
+        let mut random = Random::new(seed);
+        let wander_target = random.unit_vector();
+        Self {
+            behavior,
+            max_force,
+            max_speed,
+            wander_target,
+            random,
+        }
+    }
+
+    /// Changes the behaviour, e.g. to update a seek target each frame
+    pub fn set_behavior(&mut self, behavior: SteeringBehavior) {
+        self.behavior = behavior;
+    }
+
+    pub fn set_max_force(&mut self, max_force: Real) {
+        self.max_force = max_force;
+    }
+
+    pub fn set_max_speed(&mut self, max_speed: Real) {
+        assert_positive_speed(max_speed);
+        self.max_speed = max_speed;
+    }
+}
+
+/// Pursuit divides by the maximum speed to find how far to look ahead
+fn assert_positive_speed(max_speed: Real) {
+    assert!(
+        max_speed > 0.0,
+        "attempted to steer with a zero or negative max speed",
+    );
+}
+
+/// Returns a vector along the given direction with the given length,
+/// or zero if the direction is zero
+pub(crate) fn towards(direction: Vector3, length: Real) -> Vector3 {
+    let magnitude = direction.magnitude();
+    if magnitude == 0.0 {
+        Vector3::default()
+    } else {
+        direction * (length / magnitude)
+    }
+}
+
+/// Returns the force turning the particle's velocity towards the desired
+/// velocity within a second, limited to the maximum force.
+/// Desired velocities never exceed the maximum speed, so steering
+/// also slows down a particle which is going too fast.
+pub(crate) fn steer(particle: &Particle, desired: Vector3, max_force: Real) -> Vector3 {
+    let force = (desired - particle.get_velocity()) * particle.get_mass();
+    if force.magnitude() > max_force {
+        towards(force, max_force)
+    } else {
+        force
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::particle_forces::ParticleForceRegistry;
+
+    fn particle_at(x: Real, y: Real) -> Rc<RefCell<Particle>> {
+        let mut particle = Particle::default();
+        particle.set_mass(1.0);
+        particle.set_damping(1.0);
+        particle.set_position(x, y, 0.0);
+        Rc::new(RefCell::new(particle))
+    }
+
+    #[test]
+    fn pursuit_leads_a_moving_quarry() {
+        let quarry = particle_at(10.0, 0.0);
+        quarry.borrow_mut().set_velocity(0.0, 1.0, 0.0);
+        let hunter = particle_at(0.0, 0.0);
+
+        let pursue = SteeringBehavior::Pursue(quarry.clone());
+        let mut steering = ParticleSteering::new(pursue, 100.0, 5.0);
+        steering.update_force(&mut hunter.borrow_mut(), 0.1);
+
+        // The quarry is two seconds away, by when it will have moved up by 2
+        let force = hunter.borrow().get_accumulated_force();
+        assert!((force.x - 5.0 * 10.0 / (104.0 as Real).sqrt()).abs() < 1e-4);
+        assert!((force.y - 5.0 * 2.0 / (104.0 as Real).sqrt()).abs() < 1e-4);
+    }
+
+    #[test]
+    fn pursuing_itself_does_nothing() {
+        let particle = particle_at(0.0, 0.0);
+        let pursue = SteeringBehavior::Pursue(particle.clone());
+        let steering = Rc::new(RefCell::new(ParticleSteering::new(pursue, 1.0, 1.0)));
+
+        let mut registry = ParticleForceRegistry::new();
+        registry.add(particle.clone(), steering);
+        registry.update_forces(0.1);
+        assert_eq!(particle.borrow().get_accumulated_force().magnitude(), 0.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn rejects_a_zero_max_speed() {
+        let mut steering =
+            ParticleSteering::new(SteeringBehavior::Seek(Vector3::default()), 1.0, 1.0);
+        steering.set_max_speed(0.0);
+    }
+}
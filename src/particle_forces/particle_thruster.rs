@@ -0,0 +1,233 @@
+use crate::math::{Real, Vector3};
+use crate::particle::Particle;
+use crate::particle_forces::ParticleForceGenerator;
+
+type BurnoutCallback = Box<dyn FnMut(&mut Particle)>;
+
+/// The direction in which a thruster pushes
+#[derive(Clone, Copy)]
+pub enum ThrustDirection {
+    /// Along a fixed unit direction
+    Fixed(Vector3),
+
+    /// Along the particle's current velocity, as in a gravity turn
+    AlongVelocity,
+}
+
+/// Generates rocket thrust by burning fuel, reducing the particle's mass
+/// as the fuel is used up. Since it tracks the fuel of a single rocket,
+/// each thruster should only be registered on one particle.
+pub struct ParticleThruster {
+    direction: ThrustDirection,
+
+    /// The mass of fuel burnt per second at full throttle
+    burn_rate: Real,
+
+    /// The speed of the exhaust relative to the rocket
+    exhaust_velocity: Real,
+
+    /// The mass of the rocket without its fuel, which it never burns below
+    dry_mass: Real,
+
+    /// The mass of fuel left to burn, which is part of the particle's mass
+    fuel: Real,
+
+    /// The proportion of the full burn rate in use (0.0 = off, 1.0 = full)
+    throttle: Real,
+
+    /// Called once, when the last of the fuel has been burnt
+    on_burnout: Option<BurnoutCallback>,
+    burnt_out: bool,
+}
+
+impl ParticleForceGenerator for ParticleThruster {
+    fn update_force(&mut self, particle: &mut Particle, duration: Real) {
+        if self.burnt_out || !particle.has_finite_mass() {
+            return;
+        }
+
+        let direction = match self.direction {
+            ThrustDirection::Fixed(direction) => direction,
+            ThrustDirection::AlongVelocity => {
+                let velocity = particle.get_velocity();
+                let speed = velocity.magnitude();
+                if speed == 0.0 {
+                    return;
+                }
+                velocity * (1.0 / speed)
+            }
+        };
+
+        // Thrust is the momentum carried away by the exhaust each second
+        let burnt = (self.burn_rate * self.throttle * duration).min(self.fuel);
+        let thrust = burnt / duration * self.exhaust_velocity;
+        particle.add_force(&(direction * thrust));
+
+        self.fuel -= burnt;
+        particle.set_mass((particle.get_mass() - burnt).max(self.dry_mass));
+
+        if self.fuel <= 0.0 {
+            self.burnt_out = true;
+            if let Some(on_burnout) = &mut self.on_burnout {
+                on_burnout(particle);
+            }
+        }
+    }
+}
+
+impl ParticleThruster {
+    /// Creates a thruster for a rocket with the given dry mass and fuel,
+    /// whose sum should be the mass of the particle it is registered on
+    pub fn new(
+        direction: ThrustDirection,
+        burn_rate: Real,
+        exhaust_velocity: Real,
+        dry_mass: Real,
+        fuel: Real,
+    ) -> Self {
+        assert!(
+            dry_mass > 0.0,
+            "attempted to create a thruster with a zero or negative dry mass",
+        );
+        assert!(
+            fuel >= 0.0,
+            "attempted to create a thruster with negative fuel",
+        );
+
+        let direction = match direction {
+            ThrustDirection::Fixed(mut direction) => {
+                direction.normalize();
+                ThrustDirection::Fixed(direction)
+            }
+            ThrustDirection::AlongVelocity => ThrustDirection::AlongVelocity,
+        };
+
+        Self {
+            direction,
+            burn_rate,
+            exhaust_velocity,
+            dry_mass,
+            fuel,
+            throttle: 1.0,
+            on_burnout: None,
+            burnt_out: fuel <= 0.0,
+        }
+    }
+
+    /// Sets a callback to run when the fuel runs out,
+    /// e.g. to drop a spent stage or start a parachute
+    pub fn on_burnout(&mut self, callback: impl FnMut(&mut Particle) + 'static) {
+        self.on_burnout = Some(Box::new(callback));
+    }
+
+    /// Returns the thrust at full throttle
+    pub fn max_thrust(&self) -> Real {
+        self.burn_rate * self.exhaust_velocity
+    }
+
+    pub fn get_fuel(&self) -> Real {
+        self.fuel
+    }
+
+    pub fn has_burnt_out(&self) -> bool {
+        self.burnt_out
+    }
+
+    pub fn set_direction(&mut self, direction: ThrustDirection) {
+        self.direction = direction;
+    }
+
+    pub fn set_throttle(&mut self, throttle: Real) {
+        self.throttle = throttle.clamp(0.0, 1.0);
+    }
+
+    pub fn get_dry_mass(&self) -> Real {
+        self.dry_mass
+    }
+
+    /// Returns the change in velocity the remaining fuel can give the rocket,
+    /// by the Tsiolkovsky rocket equation
+    pub fn remaining_delta_v(&self) -> Real {
+        self.exhaust_velocity * ((self.dry_mass + self.fuel) / self.dry_mass).ln()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    fn rocket(mass: Real) -> Particle {
+        let mut particle = Particle::default();
+        particle.set_mass(mass);
+        particle.set_damping(1.0);
+        particle
+    }
+
+    #[test]
+    fn burns_fuel_down_to_the_dry_mass() {
+        let up = ThrustDirection::Fixed(Vector3::new(0.0, 2.0, 0.0));
+        let mut thruster = ParticleThruster::new(up, 1.0, 10.0, 2.0, 1.5);
+        let mut particle = rocket(3.5);
+
+        thruster.update_force(&mut particle, 1.0);
+        assert_eq!(particle.get_accumulated_force().y, 10.0);
+        assert!((particle.get_mass() - 2.5).abs() < 1e-5);
+        assert!(!thruster.has_burnt_out());
+
+        // Only the half unit of fuel left is burnt, giving half the thrust
+        particle.clear_accumulator();
+        thruster.update_force(&mut particle, 1.0);
+        assert_eq!(particle.get_accumulated_force().y, 5.0);
+        assert!((particle.get_mass() - 2.0).abs() < 1e-5);
+        assert!(thruster.has_burnt_out());
+
+        particle.clear_accumulator();
+        thruster.update_force(&mut particle, 1.0);
+        assert_eq!(particle.get_accumulated_force().y, 0.0);
+        assert!((particle.get_mass() - 2.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn never_burns_below_the_dry_mass() {
+        // The particle is lighter than the rocket's dry mass and fuel
+        let up = ThrustDirection::Fixed(Vector3::new(0.0, 1.0, 0.0));
+        let mut thruster = ParticleThruster::new(up, 10.0, 1.0, 1.0, 5.0);
+        let mut particle = rocket(2.0);
+        thruster.update_force(&mut particle, 1.0);
+        assert!((particle.get_mass() - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn calls_back_once_on_burnout() {
+        let calls = Rc::new(Cell::new(0));
+        let up = ThrustDirection::Fixed(Vector3::new(0.0, 1.0, 0.0));
+        let mut thruster = ParticleThruster::new(up, 1.0, 10.0, 1.0, 1.0);
+        let counter = calls.clone();
+        thruster.on_burnout(move |particle| {
+            counter.set(counter.get() + 1);
+            particle.set_mass(0.5);
+        });
+
+        let mut particle = rocket(2.0);
+        for _ in 0..4 {
+            thruster.update_force(&mut particle, 0.5);
+        }
+        assert_eq!(calls.get(), 1);
+        assert!((particle.get_mass() - 0.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn delta_v_follows_the_rocket_equation() {
+        let thruster = ParticleThruster::new(ThrustDirection::AlongVelocity, 1.0, 3.0, 1.0, 1.0);
+        assert!((thruster.remaining_delta_v() - 3.0 * (2.0 as Real).ln()).abs() < 1e-6);
+    }
+
+    #[test]
+    #[should_panic]
+    fn rejects_a_rocket_with_no_dry_mass() {
+        ParticleThruster::new(ThrustDirection::AlongVelocity, 1.0, 1.0, 0.0, 1.0);
+    }
+}
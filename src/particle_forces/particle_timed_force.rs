@@ -0,0 +1,189 @@
+use crate::math::{Real, Vector3};
+use crate::particle::Particle;
+use crate::particle_forces::ParticleForceGenerator;
+
+/// How a keyframed force moves between its keyframes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Holds each keyframe's force until the next keyframe
+    Step,
+
+    /// Blends straight from one keyframe to the next
+    Linear,
+
+    /// Passes smoothly through the keyframes along a Catmull-Rom style spline
+    Cubic,
+}
+
+/// Describes a force as a function of time
+pub enum ForceCurve {
+    /// A force at each of a sequence of times, sorted by time
+    Keyframes {
+        keys: Vec<(Real, Vector3)>,
+        interpolation: Interpolation,
+    },
+
+    /// A force given by any function of time
+    Custom(Box<dyn Fn(Real) -> Vector3>),
+}
+
+impl ForceCurve {
+    /// Creates a curve through the given keyframes, which are sorted by time
+    pub fn keyframes(keys: Vec<(Real, Vector3)>, interpolation: Interpolation) -> Self {
+        let mut keys = keys;
+        keys.sort_by(|a, b| a.0.partial_cmp(&b.0).expect("keyframe time is NaN"));
+        Self::Keyframes {
+            keys,
+            interpolation,
+        }
+    }
+
+    pub fn custom(curve: impl Fn(Real) -> Vector3 + 'static) -> Self {
+        Self::Custom(Box::new(curve))
+    }
+
+    /// Returns the force at the given time.
+    /// Keyframed forces hold their first and last values outside the keyframes.
+    pub fn sample(&self, time: Real) -> Vector3 {
+        let (keys, interpolation) = match self {
+            ForceCurve::Custom(curve) => return curve(time),
+            ForceCurve::Keyframes {
+                keys,
+                interpolation,
+            } => (keys, *interpolation),
+        };
+
+        let next = keys.iter().position(|key| key.0 > time);
+        let index = match next {
+            None => return keys.last().map_or(Vector3::default(), |key| key.1),
+            Some(0) => return keys[0].1,
+            Some(next) => next - 1,
+        };
+
+        let (t0, p0) = keys[index];
+        let (t1, p1) = keys[index + 1];
+        let span = t1 - t0;
+        let s = (time - t0) / span;
+
+        match interpolation {
+            Interpolation::Step => p0,
+            Interpolation::Linear => p0 * (1.0 - s) + p1 * s,
+            Interpolation::Cubic => {
+                // Tangents are the slopes between the neighbouring keyframes
+                let tangent = |i: usize| {
+                    let before = keys[i.saturating_sub(1)];
+                    let after = keys[(i + 1).min(keys.len() - 1)];
+                    (after.1 - before.1) * (1.0 / (after.0 - before.0))
+                };
+                let (m0, m1) = (tangent(index), tangent(index + 1));
+
+                // Cubic Hermite basis functions
+                let h00 = 2.0 * s.powi(3) - 3.0 * s.powi(2) + 1.0;
+                let h10 = s.powi(3) - 2.0 * s.powi(2) + s;
+                let h01 = -2.0 * s.powi(3) + 3.0 * s.powi(2);
+                let h11 = s.powi(3) - s.powi(2);
+                p0 * h00 + m0 * (h10 * span) + p1 * h01 + m1 * (h11 * span)
+            }
+        }
+    }
+}
+
+/// Generates a force which changes over simulation time, such as a thruster
+/// ramping up, measured from when the generator was created
+pub struct ParticleTimedForce {
+    curve: ForceCurve,
+    time: Real,
+
+    /// If set, time wraps back to zero after this period
+    period: Option<Real>,
+}
+
+impl ParticleForceGenerator for ParticleTimedForce {
+    fn update_force(&mut self, particle: &mut Particle, _duration: Real) {
+        particle.add_force(&self.curve.sample(self.time));
+    }
+
+    fn advance(&mut self, duration: Real) {
+        self.time += duration;
+        if let Some(period) = self.period {
+            self.time %= period;
+        }
+    }
+}
+
+impl ParticleTimedForce {
+    pub fn new(curve: ForceCurve) -> Self {
+        Self {
+            curve,
+            time: 0.0,
+            period: None,
+        }
+    }
+
+    /// Repeats the curve every period
+    pub fn looping(curve: ForceCurve, period: Real) -> Self {
+        assert!(
+            period > 0.0,
+            "attempted to loop over a zero or negative period"
+        );
+        Self {
+            curve,
+            time: 0.0,
+            period: Some(period),
+        }
+    }
+
+    pub fn get_time(&self) -> Real {
+        self.time
+    }
+
+    pub fn set_time(&mut self, time: Real) {
+        self.time = time;
+    }
+
+    /// Returns the force currently being applied
+    pub fn current_force(&self) -> Vector3 {
+        self.curve.sample(self.time)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ramp(interpolation: Interpolation) -> ForceCurve {
+        ForceCurve::keyframes(
+            vec![
+                (2.0, Vector3::new(0.0, 0.0, 0.0)),
+                (0.0, Vector3::new(0.0, 0.0, 0.0)),
+                (1.0, Vector3::new(4.0, 0.0, 0.0)),
+            ],
+            interpolation,
+        )
+    }
+
+    #[test]
+    fn keyframes_interpolate_between_sorted_keys() {
+        assert_eq!(ramp(Interpolation::Step).sample(0.5).x, 0.0);
+        assert!((ramp(Interpolation::Linear).sample(0.25).x - 1.0).abs() < 1e-6);
+        assert!((ramp(Interpolation::Linear).sample(1.5).x - 2.0).abs() < 1e-6);
+
+        // The cubic curve passes through its keyframes and holds them outside
+        let cubic = ramp(Interpolation::Cubic);
+        assert!((cubic.sample(1.0).x - 4.0).abs() < 1e-6);
+        assert_eq!(cubic.sample(-1.0).x, 0.0);
+        assert_eq!(cubic.sample(3.0).x, 0.0);
+    }
+
+    #[test]
+    fn looping_wraps_time() {
+        let mut force = ParticleTimedForce::looping(
+            ForceCurve::custom(|time| Vector3::new(time, 0.0, 0.0)),
+            1.0,
+        );
+        for _ in 0..5 {
+            force.advance(0.3);
+        }
+        assert!((force.current_force().x - 0.5).abs() < 1e-5);
+    }
+}
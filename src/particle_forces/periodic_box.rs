@@ -0,0 +1,41 @@
+use crate::math::Vector3;
+
+/// An axis-aligned box from the origin whose opposite faces are joined,
+/// so that a particle leaving one side re-enters from the other
+#[derive(Clone, Copy)]
+pub struct PeriodicBox {
+    size: Vector3,
+}
+
+impl PeriodicBox {
+    pub fn new(size: Vector3) -> Self {
+        assert!(
+            size.x > 0.0 && size.y > 0.0 && size.z > 0.0,
+            "attempted to create a periodic box with zero or negative size",
+        );
+        Self { size }
+    }
+
+    pub fn get_size(&self) -> Vector3 {
+        self.size
+    }
+
+    /// Returns the point inside the box equivalent to the given point
+    pub fn wrap(&self, point: &Vector3) -> Vector3 {
+        Vector3::new(
+            point.x.rem_euclid(self.size.x),
+            point.y.rem_euclid(self.size.y),
+            point.z.rem_euclid(self.size.z),
+        )
+    }
+
+    /// Returns the shortest vector equivalent to the given separation
+    /// between two points, by the minimum image convention
+    pub fn minimum_image(&self, difference: &Vector3) -> Vector3 {
+        Vector3::new(
+            difference.x - self.size.x * (difference.x / self.size.x).round(),
+            difference.y - self.size.y * (difference.y / self.size.y).round(),
+            difference.z - self.size.z * (difference.z / self.size.z).round(),
+        )
+    }
+}
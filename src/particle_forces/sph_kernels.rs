@@ -0,0 +1,82 @@
+//! Smoothing kernels for smoothed-particle hydrodynamics,
+//! as described by Müller et al. (2003). Each vanishes beyond
+//! the smoothing radius `h`.
+
+use crate::math::{Real, Vector3};
+
+use std::f32::consts::PI;
+
+/// The poly6 kernel, used to smooth densities
+pub fn poly6(distance: Real, h: Real) -> Real {
+    if distance >= h {
+        return 0.0;
+    }
+    315.0 / (64.0 * PI * h.powi(9)) * (h.powi(2) - distance.powi(2)).powi(3)
+}
+
+/// The gradient of the poly6 kernel with respect to the offset
+pub fn poly6_gradient(offset: &Vector3, h: Real) -> Vector3 {
+    let square_distance = offset.square_magnitude();
+    if square_distance >= h.powi(2) {
+        return Vector3::default();
+    }
+    *offset * (-945.0 / (32.0 * PI * h.powi(9)) * (h.powi(2) - square_distance).powi(2))
+}
+
+/// The Laplacian of the poly6 kernel
+pub fn poly6_laplacian(distance: Real, h: Real) -> Real {
+    if distance >= h {
+        return 0.0;
+    }
+    let square_distance = distance.powi(2);
+    -945.0 / (32.0 * PI * h.powi(9))
+        * (h.powi(2) - square_distance)
+        * (3.0 * h.powi(2) - 7.0 * square_distance)
+}
+
+/// The gradient of the spiky kernel, whose slope stays steep up close
+/// so that pressure keeps particles from clumping
+pub fn spiky_gradient(offset: &Vector3, h: Real) -> Vector3 {
+    let distance = offset.magnitude();
+    if distance >= h || distance == 0.0 {
+        return Vector3::default();
+    }
+    *offset * (-45.0 / (PI * h.powi(6)) * (h - distance).powi(2) / distance)
+}
+
+/// The Laplacian of the viscosity kernel, which is positive everywhere
+/// so that viscosity only ever damps relative motion
+pub fn viscosity_laplacian(distance: Real, h: Real) -> Real {
+    if distance >= h {
+        return 0.0;
+    }
+    45.0 / (PI * h.powi(6)) * (h - distance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn poly6_integrates_to_one() {
+        let h = 0.5;
+        let steps = 1000;
+        let dr = h / steps as Real;
+        let total: Real = (0..steps)
+            .map(|i| {
+                let r = (i as Real + 0.5) * dr;
+                4.0 * PI * r.powi(2) * poly6(r, h) * dr
+            })
+            .sum();
+        assert!((total - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn spiky_gradient_points_inwards() {
+        let offset = Vector3::new(0.2, 0.0, 0.0);
+        let gradient = spiky_gradient(&offset, 0.5);
+        assert!(gradient.x < 0.0 && gradient.y == 0.0);
+        assert_eq!(spiky_gradient(&Vector3::default(), 0.5).x, 0.0);
+        assert_eq!(viscosity_laplacian(0.6, 0.5), 0.0);
+    }
+}
@@ -0,0 +1,143 @@
+use crate::math::Real;
+use crate::particle::Particle;
+
+use std::f32::consts::PI;
+
+/// The relative tolerance within which a spring is considered critically damped
+pub(crate) const CRITICAL_TOLERANCE: Real = 1e-4;
+
+/// Describes how a damped spring returns to its rest length
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpringDamping {
+    /// Oscillates about the rest length with decaying amplitude
+    Underdamped,
+
+    /// Returns to the rest length as fast as possible without oscillating
+    CriticallyDamped,
+
+    /// Returns to the rest length slowly without oscillating
+    Overdamped,
+}
+
+impl SpringDamping {
+    /// Classifies the continuous motion of a spring with the given damping ratio
+    pub fn from_ratio(damping_ratio: Real) -> Self {
+        if (damping_ratio - 1.0).abs() <= CRITICAL_TOLERANCE {
+            Self::CriticallyDamped
+        } else if damping_ratio < 1.0 {
+            Self::Underdamped
+        } else {
+            Self::Overdamped
+        }
+    }
+
+    /// Classifies the motion of a spring as it is actually integrated
+    /// by `Particle::integrate` over steps of the given duration.
+    /// The particle's own velocity damping is included, and large steps
+    /// can make a spring oscillate even when its damping ratio is above one.
+    pub fn from_step(
+        mass: Real,
+        spring_constant: Real,
+        damping: Real,
+        particle_damping: Real,
+        duration: Real,
+    ) -> Self {
+        assert!(
+            duration > 0.0,
+            "attempted to classify a spring over a zero or negative duration",
+        );
+
+        // One step maps (x, v) to (x + hv, D(v - h(kx + cv)/m)), which is linear.
+        // The motion oscillates when that map has complex eigenvalues.
+        let retained = particle_damping.powf(duration);
+        let a = (1.0 - duration * damping / mass) * retained;
+        let b = duration.powi(2) * spring_constant / mass * retained;
+        let discriminant = (1.0 - a).powi(2) - 4.0 * b;
+
+        if discriminant.abs() <= CRITICAL_TOLERANCE * 4.0 * b {
+            Self::CriticallyDamped
+        } else if discriminant < 0.0 {
+            Self::Underdamped
+        } else {
+            Self::Overdamped
+        }
+    }
+}
+
+/// Calculates the spring constant and damping coefficient giving a spring
+/// attached to the given mass the given natural frequency (in hertz)
+/// and damping ratio (1.0 = critically damped)
+pub fn spring_constants(mass: Real, frequency: Real, damping_ratio: Real) -> (Real, Real) {
+    assert!(
+        mass.is_finite(),
+        "attempted to tune a spring attached only to infinite masses",
+    );
+
+    let angular_frequency = 2.0 * PI * frequency;
+    let spring_constant = mass * angular_frequency.powi(2);
+    let damping = 2.0 * damping_ratio * mass * angular_frequency;
+    (spring_constant, damping)
+}
+
+/// Returns the damping ratio of a spring attached to the given mass
+pub fn damping_ratio(mass: Real, spring_constant: Real, damping: Real) -> Real {
+    damping / (2.0 * (spring_constant * mass).sqrt())
+}
+
+/// Returns the mass with which two particles joined by a spring oscillate
+/// relative to one another, assuming the spring acts on both of them
+pub fn reduced_mass(particle: &Particle, other: &Particle) -> Real {
+    let inverse_mass = particle.get_inverse_mass() + other.get_inverse_mass();
+    if inverse_mass == 0.0 {
+        Real::INFINITY
+    } else {
+        1.0 / inverse_mass
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_damping_ratios() {
+        assert_eq!(SpringDamping::from_ratio(0.5), SpringDamping::Underdamped);
+        assert_eq!(
+            SpringDamping::from_ratio(1.0),
+            SpringDamping::CriticallyDamped
+        );
+        assert_eq!(SpringDamping::from_ratio(2.0), SpringDamping::Overdamped);
+    }
+
+    #[test]
+    fn constants_give_requested_ratio() {
+        let (spring_constant, damping) = spring_constants(2.0, 1.5, 0.3);
+        assert!((damping_ratio(2.0, spring_constant, damping) - 0.3).abs() < 1e-5);
+    }
+
+    #[test]
+    fn short_steps_match_continuous_motion() {
+        for &ratio in &[0.5, 2.0] {
+            let (spring_constant, damping) = spring_constants(1.0, 1.0, ratio);
+            assert_eq!(
+                SpringDamping::from_step(1.0, spring_constant, damping, 1.0, 1e-3),
+                SpringDamping::from_ratio(ratio),
+            );
+        }
+    }
+
+    #[test]
+    fn long_steps_with_particle_damping_oscillate() {
+        // Velocity damping shrinks the step's damping term below the
+        // spring's, leaving a slightly overdamped spring oscillating
+        let (spring_constant, damping) = spring_constants(1.0, 1.0, 1.01);
+        assert_eq!(
+            SpringDamping::from_step(1.0, spring_constant, damping, 1.0, 1e-3),
+            SpringDamping::Overdamped,
+        );
+        assert_eq!(
+            SpringDamping::from_step(1.0, spring_constant, damping, 0.5, 0.2),
+            SpringDamping::Underdamped,
+        );
+    }
+}
@@ -0,0 +1,291 @@
+use crate::math::{Real, Vector3};
+use crate::particle::Particle;
+use crate::particle_forces::particle_force_registry::Registration;
+use crate::particle_forces::{
+    ParticleForceGenerator, ParticleForceRegistry, ParticleRod, ParticleSpring,
+};
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// How neighbouring particles of a rope are joined
+#[derive(Clone, Copy)]
+pub enum RopeLink {
+    /// A stretchy rope, like elastic or a bungee cord
+    Spring { stiffness: Real, damping: Real },
+
+    /// An inextensible rope or chain
+    Rod,
+}
+
+/// What an end of a rope is attached to
+pub enum RopeEnd {
+    /// A loose end
+    Free,
+
+    /// A fixed point in space
+    Anchored,
+
+    /// An existing particle, which becomes the end of the rope
+    /// but is not integrated by it
+    Particle(Rc<RefCell<Particle>>),
+}
+
+/// A chain of particles joined by springs or rods
+pub struct Rope {
+    /// Every particle along the rope, from start to end
+    particles: Vec<Rc<RefCell<Particle>>>,
+
+    /// Whether each particle was created by the rope, and so is integrated by it
+    owned: Vec<bool>,
+
+    links: Vec<Registration>,
+}
+
+impl Rope {
+    pub fn get_particles(&self) -> &[Rc<RefCell<Particle>>] {
+        &self.particles
+    }
+
+    pub fn start(&self) -> &Rc<RefCell<Particle>> {
+        &self.particles[0]
+    }
+
+    pub fn end(&self) -> &Rc<RefCell<Particle>> {
+        &self.particles[self.particles.len() - 1]
+    }
+
+    /// Registers every link of the rope, adding further generators
+    /// (e.g. gravity) to every particle the rope created
+    pub fn register(
+        &self,
+        registry: &mut ParticleForceRegistry,
+        generators: &[Rc<RefCell<dyn ParticleForceGenerator>>],
+    ) {
+        for (particle, link) in &self.links {
+            registry.add(particle.clone(), link.clone());
+        }
+        for (particle, _) in self.owned_particles() {
+            for generator in generators {
+                registry.add(particle.clone(), generator.clone());
+            }
+        }
+    }
+
+    /// Integrates every particle the rope created
+    pub fn integrate(&self, duration: Real) {
+        for (particle, _) in self.owned_particles() {
+            particle.borrow_mut().integrate(duration);
+        }
+    }
+
+    /// Returns the current length along the rope
+    pub fn length(&self) -> Real {
+        self.particles
+            .windows(2)
+            .map(|pair| {
+                (pair[1].borrow().get_position() - pair[0].borrow().get_position()).magnitude()
+            })
+            .sum()
+    }
+
+    fn owned_particles(&self) -> impl Iterator<Item = (&Rc<RefCell<Particle>>, bool)> {
+        self.particles
+            .iter()
+            .zip(self.owned.iter().copied())
+            .filter(|(_, owned)| *owned)
+    }
+}
+
+/// Builds a rope stretched in a straight line between two points
+pub struct RopeBuilder {
+    start: Vector3,
+    end: Vector3,
+    segments: usize,
+
+    /// The length of the rope at rest, which may be longer than
+    /// the distance between the ends to let it sag
+    length: Option<Real>,
+
+    mass_per_length: Real,
+    particle_damping: Real,
+    link: RopeLink,
+
+    start_end: RopeEnd,
+    finish_end: RopeEnd,
+}
+
+impl RopeBuilder {
+    pub fn new(start: Vector3, end: Vector3, segments: usize) -> Self {
+        assert!(segments >= 1, "attempted to build a rope with no segments");
+
+        Self {
+            start,
+            end,
+            segments,
+            length: None,
+            mass_per_length: 1.0,
+            particle_damping: 0.99,
+            link: RopeLink::Rod,
+            start_end: RopeEnd::Free,
+            finish_end: RopeEnd::Free,
+        }
+    }
+
+    /// Sets the length of the rope at rest, e.g. longer than
+    /// the distance between its ends so that it sags
+    pub fn length(mut self, length: Real) -> Self {
+        self.length = Some(length);
+        self
+    }
+
+    pub fn mass_per_length(mut self, mass_per_length: Real) -> Self {
+        self.mass_per_length = mass_per_length;
+        self
+    }
+
+    pub fn particle_damping(mut self, damping: Real) -> Self {
+        self.particle_damping = damping;
+        self
+    }
+
+    pub fn link(mut self, link: RopeLink) -> Self {
+        self.link = link;
+        self
+    }
+
+    /// Attaches the start of the rope. An attached particle's own
+    /// position is used in place of the start point.
+    pub fn attach_start(mut self, attachment: RopeEnd) -> Self {
+        self.start_end = attachment;
+        self
+    }
+
+    /// Attaches the end of the rope. An attached particle's own
+    /// position is used in place of the end point.
+    pub fn attach_end(mut self, attachment: RopeEnd) -> Self {
+        self.finish_end = attachment;
+        self
+    }
+
+    pub fn build(self) -> Rope {
+        let start = match &self.start_end {
+            RopeEnd::Particle(particle) => particle.borrow().get_position(),
+            _ => self.start,
+        };
+        let end = match &self.finish_end {
+            RopeEnd::Particle(particle) => particle.borrow().get_position(),
+            _ => self.end,
+        };
+        let length = self.length.unwrap_or_else(|| (end - start).magnitude());
+        let segment_length = length / self.segments as Real;
+        let particle_mass = self.mass_per_length * length / (self.segments + 1) as Real;
+
+        let mut particles = Vec::with_capacity(self.segments + 1);
+        let mut owned = Vec::with_capacity(self.segments + 1);
+        for index in 0..=self.segments {
+            let attachment = if index == 0 {
+                &self.start_end
+            } else if index == self.segments {
+                &self.finish_end
+            } else {
+                &RopeEnd::Free
+            };
+
+            if let RopeEnd::Particle(particle) = attachment {
+                particles.push(particle.clone());
+                owned.push(false);
+                continue;
+            }
+
+            let position = start + (end - start) * (index as Real / self.segments as Real);
+            let mut particle = Particle::default();
+            particle.set_position(position.x, position.y, position.z);
+            particle.set_damping(self.particle_damping);
+            if let RopeEnd::Anchored = attachment {
+                particle.set_inverse_mass(0.0);
+            } else {
+                particle.set_mass(particle_mass);
+            }
+            particles.push(Rc::new(RefCell::new(particle)));
+            owned.push(true);
+        }
+
+        // Join each neighbouring pair with a generator acting on each end
+        let mut links: Vec<Registration> = Vec::with_capacity(2 * self.segments);
+        for pair in particles.windows(2) {
+            for (particle, other) in [(&pair[0], &pair[1]), (&pair[1], &pair[0])] {
+                let link: Rc<RefCell<dyn ParticleForceGenerator>> = match self.link {
+                    RopeLink::Spring { stiffness, damping } => {
+                        Rc::new(RefCell::new(ParticleSpring::with_damping(
+                            other.clone(),
+                            stiffness,
+                            damping,
+                            segment_length,
+                        )))
+                    }
+                    RopeLink::Rod => Rc::new(RefCell::new(ParticleRod::new(
+                        other.clone(),
+                        segment_length,
+                    ))),
+                };
+                links.push((particle.clone(), link));
+            }
+        }
+
+        Rope {
+            particles,
+            owned,
+            links,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::particle_forces::ParticleGravity;
+
+    #[test]
+    fn shares_mass_between_segments() {
+        let rope = RopeBuilder::new(Vector3::default(), Vector3::new(2.0, 0.0, 0.0), 4)
+            .mass_per_length(2.5)
+            .build();
+        assert_eq!(rope.get_particles().len(), 5);
+        assert!((rope.length() - 2.0).abs() < 1e-6);
+        assert!((rope.start().borrow().get_mass() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn hanging_chain_keeps_its_length() {
+        let load = Rc::new(RefCell::new(Particle::default()));
+        load.borrow_mut().set_position(1.0, 0.0, 0.0);
+        load.borrow_mut().set_mass(1.0);
+
+        let rope = RopeBuilder::new(Vector3::default(), Vector3::new(1.0, 0.0, 0.0), 5)
+            .link(RopeLink::Rod)
+            .attach_start(RopeEnd::Anchored)
+            .attach_end(RopeEnd::Particle(load.clone()))
+            .build();
+        assert!(Rc::ptr_eq(rope.end(), &load));
+
+        let mut registry = ParticleForceRegistry::new();
+        let gravity: Rc<RefCell<dyn ParticleForceGenerator>> = Rc::new(RefCell::new(
+            ParticleGravity::new(Vector3::new(0.0, -10.0, 0.0)),
+        ));
+        rope.register(&mut registry, &[gravity]);
+        for _ in 0..200 {
+            registry.update_forces(0.005);
+            rope.integrate(0.005);
+            for particle in rope.get_particles() {
+                particle.borrow_mut().clear_accumulator();
+            }
+        }
+
+        // The rope falls but never stretches, and leaves the load to its owner
+        assert_eq!(rope.start().borrow().get_position().magnitude(), 0.0);
+        assert!(rope.get_particles()[2].borrow().get_position().y < -0.05);
+        assert!(rope.length() < 1.05);
+        assert_eq!(load.borrow().get_position().y, 0.0);
+    }
+}
@@ -0,0 +1,370 @@
+use crate::math::{Real, Vector3};
+use crate::particle::Particle;
+use crate::particle_forces::particle_force_registry::Registration;
+use crate::particle_forces::particle_gas_pressure::enclosed_volume;
+use crate::particle_forces::{
+    ParticleForceGenerator, ParticleForceRegistry, ParticleGasPressure, ParticleSpring,
+};
+
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashMap};
+use std::rc::Rc;
+
+/// A closed surface of particles joined by springs
+/// and inflated by the gas sealed inside it
+pub struct SoftBody {
+    particles: Vec<Rc<RefCell<Particle>>>,
+
+    /// The faces of the surface, wound anticlockwise when seen from outside
+    triangles: Vec<[usize; 3]>,
+
+    springs: Vec<Registration>,
+    gas: Rc<RefCell<ParticleGasPressure>>,
+}
+
+impl SoftBody {
+    pub fn get_particles(&self) -> &[Rc<RefCell<Particle>>] {
+        &self.particles
+    }
+
+    pub fn get_triangles(&self) -> &[[usize; 3]] {
+        &self.triangles
+    }
+
+    pub fn get_gas(&self) -> &Rc<RefCell<ParticleGasPressure>> {
+        &self.gas
+    }
+
+    /// Registers every spring and the gas pressure, adding further
+    /// generators (e.g. gravity) to every particle
+    pub fn register(
+        &self,
+        registry: &mut ParticleForceRegistry,
+        generators: &[Rc<RefCell<dyn ParticleForceGenerator>>],
+    ) {
+        for (particle, spring) in &self.springs {
+            registry.add(particle.clone(), spring.clone());
+        }
+        for particle in &self.particles {
+            registry.add(particle.clone(), self.gas.clone());
+            for generator in generators {
+                registry.add(particle.clone(), generator.clone());
+            }
+        }
+    }
+
+    /// Integrates every particle of the body
+    pub fn integrate(&self, duration: Real) {
+        for particle in &self.particles {
+            particle.borrow_mut().integrate(duration);
+        }
+    }
+
+    /// Returns the volume currently enclosed by the surface
+    pub fn volume(&self) -> Real {
+        let positions: Vec<_> = self
+            .particles
+            .iter()
+            .map(|particle| particle.borrow().get_position())
+            .collect();
+        enclosed_volume(&positions, &self.triangles)
+    }
+
+    /// Returns the pressure of the gas at the current volume
+    pub fn pressure(&self) -> Real {
+        let volume = self.volume();
+        if volume > 0.0 {
+            self.gas.borrow().get_gas_amount() / volume
+        } else {
+            0.0
+        }
+    }
+
+    /// Scales the amount of gas inside the body,
+    /// e.g. by 2.0 to double its pressure at the current volume
+    pub fn inflate(&self, factor: Real) {
+        let mut gas = self.gas.borrow_mut();
+        let gas_amount = gas.get_gas_amount();
+        gas.set_gas_amount(gas_amount * factor);
+    }
+
+    /// Returns the centre of mass of the body's particles
+    pub fn centre(&self) -> Vector3 {
+        let mut total = Vector3::default();
+        let mut mass = 0.0;
+        for particle in &self.particles {
+            let particle = particle.borrow();
+            if particle.has_finite_mass() {
+                total.add_scaled_vector(&particle.get_position(), particle.get_mass());
+                mass += particle.get_mass();
+            }
+        }
+        if mass > 0.0 {
+            total * (1.0 / mass)
+        } else {
+            total
+        }
+    }
+}
+
+/// Builds a soft body from a closed triangle mesh
+pub struct SoftBodyBuilder {
+    positions: Vec<Vector3>,
+    triangles: Vec<[usize; 3]>,
+
+    /// The total mass, shared equally between the particles
+    mass: Real,
+
+    particle_damping: Real,
+    stiffness: Real,
+    spring_damping: Real,
+
+    /// The gas pressure at the mesh's initial volume
+    pressure: Real,
+}
+
+impl SoftBodyBuilder {
+    /// Starts from a closed mesh whose triangles are wound
+    /// anticlockwise when seen from outside
+    pub fn from_mesh(positions: Vec<Vector3>, triangles: Vec<[usize; 3]>) -> Self {
+        assert!(
+            !triangles.is_empty(),
+            "attempted to build a soft body with no triangles",
+        );
+        assert!(
+            triangles
+                .iter()
+                .flatten()
+                .all(|&index| index < positions.len()),
+            "attempted to build a soft body with a triangle index out of range",
+        );
+
+        Self {
+            positions,
+            triangles,
+            mass: 10.0,
+            particle_damping: 0.99,
+            stiffness: 100.0,
+            spring_damping: 1.0,
+            pressure: 10.0,
+        }
+    }
+
+    /// Starts from a geodesic sphere, made by splitting each face of an
+    /// icosahedron into four the given number of times
+    pub fn sphere(centre: Vector3, radius: Real, subdivisions: usize) -> Self {
+        assert!(
+            radius > 0.0,
+            "attempted to build a sphere with a non-positive radius"
+        );
+
+        let (positions, triangles) = icosphere(subdivisions);
+        let positions = positions
+            .into_iter()
+            .map(|direction| centre + direction * radius)
+            .collect();
+        Self::from_mesh(positions, triangles)
+    }
+
+    pub fn mass(mut self, mass: Real) -> Self {
+        self.mass = mass;
+        self
+    }
+
+    pub fn particle_damping(mut self, damping: Real) -> Self {
+        self.particle_damping = damping;
+        self
+    }
+
+    /// Sets the spring constant of the springs along the surface's edges
+    pub fn stiffness(mut self, stiffness: Real) -> Self {
+        self.stiffness = stiffness;
+        self
+    }
+
+    pub fn spring_damping(mut self, damping: Real) -> Self {
+        self.spring_damping = damping;
+        self
+    }
+
+    /// Sets the gas pressure at the mesh's initial volume
+    pub fn pressure(mut self, pressure: Real) -> Self {
+        self.pressure = pressure;
+        self
+    }
+
+    pub fn build(self) -> SoftBody {
+        let particle_mass = self.mass / self.positions.len() as Real;
+        let particles: Vec<_> = self
+            .positions
+            .iter()
+            .map(|position| {
+                let mut particle = Particle::default();
+                particle.set_position(position.x, position.y, position.z);
+                particle.set_mass(particle_mass);
+                particle.set_damping(self.particle_damping);
+                Rc::new(RefCell::new(particle))
+            })
+            .collect();
+
+        // Each edge is shared by two faces, so collect them without repeats
+        let edges: BTreeSet<(usize, usize)> = self
+            .triangles
+            .iter()
+            .flat_map(|&[a, b, c]| vec![(a, b), (b, c), (c, a)])
+            .map(|(a, b)| (a.min(b), a.max(b)))
+            .collect();
+
+        // Join the ends of each edge with a spring acting on each end
+        let mut springs: Vec<Registration> = Vec::with_capacity(2 * edges.len());
+        for (a, b) in edges {
+            let rest_length = (self.positions[b] - self.positions[a]).magnitude();
+            for (particle, other) in [(a, b), (b, a)] {
+                springs.push((
+                    particles[particle].clone(),
+                    Rc::new(RefCell::new(ParticleSpring::with_damping(
+                        particles[other].clone(),
+                        self.stiffness,
+                        self.spring_damping,
+                        rest_length,
+                    ))),
+                ));
+            }
+        }
+
+        let gas = Rc::new(RefCell::new(ParticleGasPressure::new(
+            particles.clone(),
+            self.triangles.clone(),
+            self.pressure,
+        )));
+
+        SoftBody {
+            particles,
+            triangles: self.triangles,
+            springs,
+            gas,
+        }
+    }
+}
+
+/// Returns the unit directions and outward facing triangles of a geodesic sphere
+fn icosphere(subdivisions: usize) -> (Vec<Vector3>, Vec<[usize; 3]>) {
+    let t = (1.0 + (5.0 as Real).sqrt()) / 2.0;
+    let mut positions: Vec<Vector3> = [
+        (-1.0, t, 0.0),
+        (1.0, t, 0.0),
+        (-1.0, -t, 0.0),
+        (1.0, -t, 0.0),
+        (0.0, -1.0, t),
+        (0.0, 1.0, t),
+        (0.0, -1.0, -t),
+        (0.0, 1.0, -t),
+        (t, 0.0, -1.0),
+        (t, 0.0, 1.0),
+        (-t, 0.0, -1.0),
+        (-t, 0.0, 1.0),
+    ]
+    .iter()
+    .map(|&(x, y, z)| unit(Vector3::new(x, y, z)))
+    .collect();
+    let mut triangles = vec![
+        [0, 11, 5],
+        [0, 5, 1],
+        [0, 1, 7],
+        [0, 7, 10],
+        [0, 10, 11],
+        [1, 5, 9],
+        [5, 11, 4],
+        [11, 10, 2],
+        [10, 7, 6],
+        [7, 1, 8],
+        [3, 9, 4],
+        [3, 4, 2],
+        [3, 2, 6],
+        [3, 6, 8],
+        [3, 8, 9],
+        [4, 9, 5],
+        [2, 4, 11],
+        [6, 2, 10],
+        [8, 6, 7],
+        [9, 8, 1],
+    ];
+
+    for _ in 0..subdivisions {
+        // Reuse the midpoint of each edge shared by neighbouring faces
+        let mut midpoints: HashMap<(usize, usize), usize> = HashMap::new();
+        let mut midpoint = |a: usize, b: usize, positions: &mut Vec<Vector3>| {
+            *midpoints.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                positions.push(unit(positions[a] + positions[b]));
+                positions.len() - 1
+            })
+        };
+
+        let mut split = Vec::with_capacity(4 * triangles.len());
+        for [a, b, c] in triangles {
+            let ab = midpoint(a, b, &mut positions);
+            let bc = midpoint(b, c, &mut positions);
+            let ca = midpoint(c, a, &mut positions);
+            split.push([a, ab, ca]);
+            split.push([b, bc, ab]);
+            split.push([c, ca, bc]);
+            split.push([ab, bc, ca]);
+        }
+        triangles = split;
+    }
+
+    (positions, triangles)
+}
+
+fn unit(mut vector: Vector3) -> Vector3 {
+    vector.normalize();
+    vector
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sphere_is_closed_and_round() {
+        let body = SoftBodyBuilder::sphere(Vector3::new(0.0, 2.0, 0.0), 1.0, 2).build();
+        assert_eq!(body.get_particles().len(), 162);
+        assert_eq!(body.get_triangles().len(), 320);
+
+        let volume = body.volume();
+        let sphere = 4.0 / 3.0 * std::f32::consts::PI;
+        assert!(volume < sphere && volume > 0.95 * sphere);
+        assert!((body.centre() - Vector3::new(0.0, 2.0, 0.0)).magnitude() < 1e-4);
+    }
+
+    #[test]
+    fn squashed_body_springs_back() {
+        let body = SoftBodyBuilder::sphere(Vector3::default(), 1.0, 1)
+            .stiffness(500.0)
+            .pressure(10.0)
+            .particle_damping(0.5)
+            .build();
+        let rest_volume = body.volume();
+
+        for particle in body.get_particles() {
+            let mut particle = particle.borrow_mut();
+            let position = particle.get_position();
+            particle.set_position(position.x, position.y * 0.5, position.z);
+        }
+        assert!(body.pressure() > 15.0);
+
+        let mut registry = ParticleForceRegistry::new();
+        body.register(&mut registry, &[]);
+        for _ in 0..2000 {
+            registry.update_forces(0.001);
+            body.integrate(0.001);
+            for particle in body.get_particles() {
+                particle.borrow_mut().clear_accumulator();
+            }
+        }
+        assert!((body.volume() - rest_volume).abs() < 0.1 * rest_volume);
+
+        body.inflate(2.0);
+        assert!(body.pressure() > 15.0);
+    }
+}
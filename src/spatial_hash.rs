@@ -0,0 +1,90 @@
+use crate::math::{Real, Vector3};
+
+use std::collections::HashMap;
+
+/// Buckets indexed points into a uniform grid of cubic cells,
+/// so that the points near a position can be found without
+/// checking every point
+pub struct SpatialHash {
+    cell_size: Real,
+    cells: HashMap<(i32, i32, i32), Vec<usize>>,
+}
+
+impl SpatialHash {
+    /// Creates an empty hash. Queries are fastest when the cell size
+    /// is about the same as the radius usually searched.
+    pub fn new(cell_size: Real) -> Self {
+        assert!(
+            cell_size > 0.0,
+            "attempted to create a spatial hash with zero or negative cell size",
+        );
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+        }
+    }
+
+    /// Creates a hash holding each position under its index in the slice
+    pub fn from_positions(cell_size: Real, positions: &[Vector3]) -> Self {
+        let mut hash = Self::new(cell_size);
+        for (index, position) in positions.iter().enumerate() {
+            hash.insert(index, position);
+        }
+        hash
+    }
+
+    pub fn get_cell_size(&self) -> Real {
+        self.cell_size
+    }
+
+    pub fn clear(&mut self) {
+        // Keep the buckets' allocations for the next rebuild
+        for bucket in self.cells.values_mut() {
+            bucket.clear();
+        }
+    }
+
+    pub fn insert(&mut self, index: usize, position: &Vector3) {
+        self.cells
+            .entry(self.cell(position))
+            .or_default()
+            .push(index);
+    }
+
+    /// Calls the visitor with the index of every point in the cells
+    /// overlapping a sphere, which includes every point inside it
+    pub fn for_each_candidate(&self, center: &Vector3, radius: Real, mut visit: impl FnMut(usize)) {
+        let offset = Vector3::new(radius, radius, radius);
+        let min = self.cell(&(*center - offset));
+        let max = self.cell(&(*center + offset));
+        for x in min.0..=max.0 {
+            for y in min.1..=max.1 {
+                for z in min.2..=max.2 {
+                    if let Some(bucket) = self.cells.get(&(x, y, z)) {
+                        bucket.iter().for_each(|&index| visit(index));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the indices of the given positions which lie within a sphere,
+    /// assuming the hash was built from the same positions
+    pub fn within(&self, positions: &[Vector3], center: &Vector3, radius: Real) -> Vec<usize> {
+        let mut found = Vec::new();
+        self.for_each_candidate(center, radius, |index| {
+            if (positions[index] - *center).square_magnitude() <= radius.powi(2) {
+                found.push(index);
+            }
+        });
+        found
+    }
+
+    fn cell(&self, position: &Vector3) -> (i32, i32, i32) {
+        (
+            (position.x / self.cell_size).floor() as i32,
+            (position.y / self.cell_size).floor() as i32,
+            (position.z / self.cell_size).floor() as i32,
+        )
+    }
+}
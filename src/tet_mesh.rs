@@ -0,0 +1,278 @@
+use crate::math::{Matrix3, Real, Vector3};
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// A problem reading a tetrahedral mesh
+#[derive(Debug)]
+pub enum TetMeshError {
+    Io(io::Error),
+
+    /// A malformed line, numbered from 1
+    Parse {
+        line: usize,
+        message: String,
+    },
+}
+
+impl fmt::Display for TetMeshError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "failed to read tet mesh: {}", error),
+            Self::Parse { line, message } => {
+                write!(f, "failed to parse tet mesh at line {}: {}", line, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TetMeshError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Parse { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for TetMeshError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+/// A solid made of tetrahedra, given as the positions of their corners
+/// and four corner indices for each tetrahedron
+#[derive(Clone, Default)]
+pub struct TetMesh {
+    pub positions: Vec<Vector3>,
+    pub tetrahedra: Vec<[usize; 4]>,
+}
+
+impl TetMesh {
+    pub fn new(positions: Vec<Vector3>, tetrahedra: Vec<[usize; 4]>) -> Self {
+        assert!(
+            tetrahedra
+                .iter()
+                .flatten()
+                .all(|&index| index < positions.len()),
+            "attempted to create a tet mesh with a corner index out of range",
+        );
+
+        Self {
+            positions,
+            tetrahedra,
+        }
+    }
+
+    /// Reads a mesh from a file in the format read by `parse`
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, TetMeshError> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Reads a mesh from text with one entry per line, in the style of OBJ.
+    /// A corner is written `v x y z` and a tetrahedron `t a b c d`,
+    /// where corners are numbered from 0 in the order they appear.
+    /// Blank lines and anything after a `#` are ignored.
+    /// Inverted tetrahedra are reordered, and flat ones are rejected.
+    pub fn parse(text: &str) -> Result<Self, TetMeshError> {
+        let mut positions = Vec::new();
+        let mut tetrahedra = Vec::new();
+
+        // The line each tetrahedron was read from, to report bad indices
+        // and flat tetrahedra, which can only be checked once every
+        // corner is read
+        let mut tetrahedron_lines = Vec::new();
+
+        for (number, line) in text.lines().enumerate() {
+            let error = |message: String| TetMeshError::Parse {
+                line: number + 1,
+                message,
+            };
+
+            let line = line.split('#').next().unwrap_or("");
+            let mut fields = line.split_whitespace();
+            let kind = match fields.next() {
+                Some(kind) => kind,
+                None => continue,
+            };
+            let fields: Vec<_> = fields.collect();
+
+            match kind {
+                "v" => {
+                    let values = parse_fields::<Real>(&fields, 3).map_err(error)?;
+                    positions.push(Vector3::new(values[0], values[1], values[2]));
+                }
+                "t" => {
+                    let values = parse_fields::<usize>(&fields, 4).map_err(error)?;
+                    tetrahedra.push([values[0], values[1], values[2], values[3]]);
+                    tetrahedron_lines.push(number + 1);
+                }
+                _ => return Err(error(format!("unknown entry '{}'", kind))),
+            }
+        }
+
+        for (tetrahedron, &line) in tetrahedra.iter_mut().zip(tetrahedron_lines.iter()) {
+            if let Some(&corner) = tetrahedron
+                .iter()
+                .find(|&&corner| corner >= positions.len())
+            {
+                return Err(TetMeshError::Parse {
+                    line,
+                    message: format!(
+                        "corner {} does not exist, as there are only {}",
+                        corner,
+                        positions.len(),
+                    ),
+                });
+            }
+
+            let volume = signed_volume(&positions, tetrahedron);
+            if volume == 0.0 {
+                return Err(TetMeshError::Parse {
+                    line,
+                    message: "tetrahedron has no volume".to_string(),
+                });
+            } else if volume < 0.0 {
+                tetrahedron.swap(2, 3);
+            }
+        }
+
+        Ok(Self {
+            positions,
+            tetrahedra,
+        })
+    }
+
+    /// Creates a box with one corner at `origin`, split into the given
+    /// number of cubes along each axis, each of which is split into six
+    /// tetrahedra sharing its main diagonal
+    pub fn cuboid(origin: Vector3, size: Vector3, divisions: [usize; 3]) -> Self {
+        assert!(
+            divisions.iter().all(|&count| count > 0),
+            "attempted to create a cuboid with no divisions along an axis",
+        );
+
+        let [nx, ny, nz] = divisions;
+        let index = |x: usize, y: usize, z: usize| (z * (ny + 1) + y) * (nx + 1) + x;
+
+        let mut positions = Vec::with_capacity((nx + 1) * (ny + 1) * (nz + 1));
+        for z in 0..=nz {
+            for y in 0..=ny {
+                for x in 0..=nx {
+                    positions.push(Vector3::new(
+                        origin.x + size.x * x as Real / nx as Real,
+                        origin.y + size.y * y as Real / ny as Real,
+                        origin.z + size.z * z as Real / nz as Real,
+                    ));
+                }
+            }
+        }
+
+        // Walk from the cube's lowest corner to its highest, one axis at
+        // a time, in each of the six possible orders. Neighbouring cubes
+        // split their shared faces the same way.
+        const ORDERS: [[usize; 3]; 6] = [
+            [0, 1, 2],
+            [0, 2, 1],
+            [1, 0, 2],
+            [1, 2, 0],
+            [2, 0, 1],
+            [2, 1, 0],
+        ];
+        let mut tetrahedra = Vec::with_capacity(6 * nx * ny * nz);
+        for z in 0..nz {
+            for y in 0..ny {
+                for x in 0..nx {
+                    for order in &ORDERS {
+                        let mut corner = [x, y, z];
+                        let mut tetrahedron = [index(x, y, z); 4];
+                        for (step, &axis) in order.iter().enumerate() {
+                            corner[axis] += 1;
+                            tetrahedron[step + 1] = index(corner[0], corner[1], corner[2]);
+                        }
+                        tetrahedra.push(tetrahedron);
+                    }
+                }
+            }
+        }
+
+        Self {
+            positions,
+            tetrahedra,
+        }
+    }
+}
+
+/// Finds six times a tetrahedron's volume, which is negative if its
+/// corners are ordered inside out
+fn signed_volume(positions: &[Vector3], tetrahedron: &[usize; 4]) -> Real {
+    let [a, b, c, d] = *tetrahedron;
+    Matrix3::from_columns(
+        positions[b] - positions[a],
+        positions[c] - positions[a],
+        positions[d] - positions[a],
+    )
+    .determinant()
+}
+
+fn parse_fields<T: std::str::FromStr>(fields: &[&str], count: usize) -> Result<Vec<T>, String> {
+    if fields.len() != count {
+        return Err(format!("expected {} values, found {}", count, fields.len()));
+    }
+    fields
+        .iter()
+        .map(|field| {
+            field
+                .parse()
+                .map_err(|_| format!("invalid value '{}'", field))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CORNERS: &str = "v 0 0 0\nv 1 0 0\nv 0 1 0\nv 0 0 1\n";
+
+    fn error_line(text: &str) -> usize {
+        match TetMesh::parse(text) {
+            Err(TetMeshError::Parse { line, .. }) => line,
+            Err(error) => panic!("unexpected error: {}", error),
+            Ok(_) => panic!("parsed a malformed mesh"),
+        }
+    }
+
+    #[test]
+    fn parses_corners_and_tetrahedra() {
+        let text = format!("# a single tetrahedron\n{}\nt 0 1 2 3 # comment\n", CORNERS);
+        let mesh = TetMesh::parse(&text).unwrap();
+        assert_eq!(mesh.positions.len(), 4);
+        assert_eq!(mesh.tetrahedra, vec![[0, 1, 2, 3]]);
+    }
+
+    #[test]
+    fn reports_the_line_of_malformed_entries() {
+        assert_eq!(error_line("v 0 0 0\nq 1 2 3\n"), 2);
+        assert_eq!(error_line("v 0 0\n"), 1);
+        assert_eq!(error_line("v 0 0 0\n\nv 1 x 0\n"), 3);
+        assert_eq!(error_line("t 0 1 2 3\nv 0 0 0\n"), 1);
+    }
+
+    #[test]
+    fn rejects_flat_tetrahedra() {
+        let text = format!("{}v 1 1 0\nt 0 1 2 3\nt 0 1 2 4\n", CORNERS);
+        assert_eq!(error_line(&text), 7);
+    }
+
+    #[test]
+    fn reorders_inverted_tetrahedra() {
+        let text = format!("{}t 0 1 3 2\n", CORNERS);
+        let mesh = TetMesh::parse(&text).unwrap();
+        assert_eq!(mesh.tetrahedra, vec![[0, 1, 2, 3]]);
+        assert!(signed_volume(&mesh.positions, &mesh.tetrahedra[0]) > 0.0);
+    }
+}